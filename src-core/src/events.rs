@@ -0,0 +1,126 @@
+//! Payloads of the Tauri events the app emits about stubs. Mirrored in `types.d.ts`.
+
+use serde::Serialize;
+
+/// Name of the Tauri event every [`LifecycleEvent`] is emitted under.
+pub const LIFECYCLE_EVENT: &str = "process_lifecycle";
+
+/// Name of the Tauri event carrying [`StubTrayClosed`].
+pub const TRAY_CLOSED_EVENT: &str = "stub_tray_closed";
+
+/// How a stop ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopOutcome {
+    /// Closed on request within the grace period.
+    Graceful,
+    /// Needed a force-kill after the grace period.
+    Forced,
+    /// Exited before this stop could act, e.g. through an earlier stop.
+    AlreadyExited,
+    /// Still alive after the force-kill.
+    Failed,
+}
+
+/// Fields shared by every lifecycle event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProcessInfo {
+    pub app_id: String,
+    pub executable_name: String,
+    pub game_name: String,
+    pub pid: u32,
+    /// Unix time in seconds when the event happened.
+    pub timestamp: u64,
+    /// Seconds since the process started.
+    pub duration_secs: u64,
+}
+
+/// What the webview receives, tagged by `kind`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    Started {
+        #[serde(flatten)]
+        process: ProcessInfo,
+    },
+    StopRequested {
+        #[serde(flatten)]
+        process: ProcessInfo,
+    },
+    Exited {
+        #[serde(flatten)]
+        process: ProcessInfo,
+        /// `None` when the process was killed or ended by a signal.
+        exit_code: Option<i32>,
+    },
+    ForceKilled {
+        #[serde(flatten)]
+        process: ProcessInfo,
+    },
+    /// A stop requested from the app is over; `outcome` says how it ended.
+    StopFinished {
+        #[serde(flatten)]
+        process: ProcessInfo,
+        outcome: StopOutcome,
+    },
+    /// The process could no longer be watched; it may or may not still be running.
+    MonitorError {
+        #[serde(flatten)]
+        process: ProcessInfo,
+        error: String,
+    },
+}
+
+/// A stub was closed from its tray menu rather than from the app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StubTrayClosed {
+    pub app_id: String,
+    pub executable_name: String,
+    pub game_name: String,
+}
+
+/// Name of the Tauri event carrying the whole launch queue, as a list of [`QueuedLaunch`].
+pub const LAUNCH_QUEUE_EVENT: &str = "launch_queue";
+
+/// Name of the Tauri event carrying [`QueuedLaunchFailed`].
+pub const QUEUED_LAUNCH_FAILED_EVENT: &str = "queued_launch_failed";
+
+/// A launch waiting for a running stub to exit, because the concurrency limit was reached.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueuedLaunch {
+    pub app_id: String,
+    pub executable_name: String,
+    pub game_name: String,
+    /// Unix time in seconds when the launch was queued.
+    pub queued_at: u64,
+}
+
+/// A queued launch got its turn but the stub could not be started.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueuedLaunchFailed {
+    #[serde(flatten)]
+    pub launch: QueuedLaunch,
+    pub error: String,
+}
+
+/// Name of the Tauri event carrying a periodic list of [`StubResources`], one per running stub.
+pub const STUB_RESOURCES_EVENT: &str = "stub_resources";
+
+/// What one stub costs, sampled from the OS.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StubResources {
+    pub app_id: String,
+    pub executable_name: String,
+    pub pid: u32,
+    /// Resident memory.
+    pub memory_bytes: u64,
+    pub virtual_memory_bytes: u64,
+    /// Since the previous sample; 100 is one core fully busy. 0 on the first sample.
+    pub cpu_percent: f32,
+    /// Open handles on Windows, open file descriptors elsewhere.
+    pub handles: Option<u64>,
+    /// Available on Windows and Linux.
+    pub threads: Option<u64>,
+    /// Unix time in seconds when the sample was taken.
+    pub timestamp: u64,
+}
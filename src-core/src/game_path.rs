@@ -0,0 +1,331 @@
+//! Where fake games live under the games root, and which names are allowed there.
+
+use discord_quest_i18n::{self as i18n, Lang};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Folder next to the app executable that holds every fake game.
+pub const GAMES_DIR: &str = "games";
+
+/// Folder under the games root that holds one canonical copy per stub version.
+pub const STORE_DIR: &str = ".stubs";
+
+// Names Windows reserves for devices, with or without an extension ("con.txt" is still CON)
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+const ILLEGAL_CHARS: [char; 7] = ['<', '>', ':', '"', '|', '?', '*'];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GamePathError {
+    Empty(&'static str),
+    Absolute(String),
+    Traversal(String),
+    IllegalCharacter(String),
+    ReservedName(String),
+    NotAFileName(String),
+    OutsideRoot,
+}
+
+impl GamePathError {
+    pub fn message(&self, lang: Lang) -> String {
+        match self {
+            GamePathError::Empty(field) => i18n::format(lang, "path.empty", &[field]),
+            GamePathError::Absolute(value) => i18n::format(lang, "path.absolute", &[value]),
+            GamePathError::Traversal(value) => i18n::format(lang, "path.traversal", &[value]),
+            GamePathError::IllegalCharacter(value) => {
+                i18n::format(lang, "path.illegal_character", &[value])
+            }
+            GamePathError::ReservedName(value) => {
+                i18n::format(lang, "path.reserved_name", &[value])
+            }
+            GamePathError::NotAFileName(value) => {
+                i18n::format(lang, "path.not_a_file_name", &[value])
+            }
+            GamePathError::OutsideRoot => i18n::text(lang, "path.outside_root").to_string(),
+        }
+    }
+}
+
+impl fmt::Display for GamePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Lang::default()))
+    }
+}
+
+impl std::error::Error for GamePathError {}
+
+/// Location of a fake game inside the games root, built from untrusted frontend input.
+///
+/// Every component is checked on its own, so the result always lies under
+/// `<root>/<app_id>/` no matter what the catalog or the webview sends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GamePath {
+    folder: PathBuf,
+    executable: PathBuf,
+}
+
+impl GamePath {
+    pub fn new(
+        root: &Path,
+        app_id: &str,
+        path: &str,
+        executable_name: &str,
+    ) -> Result<Self, GamePathError> {
+        let app_id = single_component(app_id, "app_id")?;
+        if !is_app_folder(&app_id) {
+            return Err(GamePathError::ReservedName(app_id));
+        }
+        let executable_name = single_component(executable_name, "executable_name")?;
+
+        let mut folder = root.join(app_id);
+        for component in relative_components(path)? {
+            folder.push(component);
+        }
+        let executable = folder.join(executable_name);
+
+        if !executable.starts_with(root) {
+            return Err(GamePathError::OutsideRoot);
+        }
+
+        Ok(GamePath { folder, executable })
+    }
+
+    /// Folder that holds the executable, `<root>/<app_id>/<path>`.
+    pub fn folder(&self) -> &Path {
+        &self.folder
+    }
+
+    /// Full path of the fake executable.
+    pub fn executable(&self) -> &Path {
+        &self.executable
+    }
+}
+
+/// The games root for an app installed at `exe_path`.
+pub fn games_root_for(exe_path: &Path) -> PathBuf {
+    let exe_dir = exe_path.parent().unwrap_or_else(|| Path::new(""));
+    exe_dir.join(GAMES_DIR)
+}
+
+/// Whether a folder directly under the games root belongs to a game. Dot
+/// folders such as [`STORE_DIR`] are the app's own.
+pub fn is_app_folder(name: &str) -> bool {
+    !name.starts_with('.')
+}
+
+/// Key under which launches of `executable` are recorded: its path below `root`, `/`-separated.
+pub fn usage_key(root: &Path, executable: &Path) -> String {
+    let relative = executable.strip_prefix(root).unwrap_or(executable);
+    relative
+        .iter()
+        .map(|part| part.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn single_component(value: &str, field: &'static str) -> Result<String, GamePathError> {
+    if value.contains(['/', '\\']) {
+        return Err(GamePathError::NotAFileName(value.to_string()));
+    }
+    match sanitize_component(value)? {
+        Some(component) => Ok(component),
+        None => Err(GamePathError::Empty(field)),
+    }
+}
+
+fn relative_components(path: &str) -> Result<Vec<String>, GamePathError> {
+    if path.starts_with(['/', '\\']) || has_drive_prefix(path) {
+        // Covers "/abs", "\abs", "\\server\share" and "C:\..."
+        return Err(GamePathError::Absolute(path.to_string()));
+    }
+
+    let mut components = Vec::new();
+    for raw in path.split(['/', '\\']) {
+        if let Some(component) = sanitize_component(raw)? {
+            components.push(component);
+        }
+    }
+    Ok(components)
+}
+
+/// Returns `None` for components that add nothing to a path ("" and ".").
+fn sanitize_component(raw: &str) -> Result<Option<String>, GamePathError> {
+    if raw.is_empty() || raw == "." {
+        return Ok(None);
+    }
+    if raw == ".." {
+        return Err(GamePathError::Traversal(raw.to_string()));
+    }
+    if raw
+        .chars()
+        .any(|c| ILLEGAL_CHARS.contains(&c) || c.is_control())
+    {
+        return Err(GamePathError::IllegalCharacter(raw.to_string()));
+    }
+
+    // Windows drops trailing dots and spaces, so ".. " would open the parent folder
+    let trimmed = raw.trim_end_matches(['.', ' ']);
+    if trimmed.is_empty() {
+        return Err(GamePathError::Traversal(raw.to_string()));
+    }
+
+    let stem = trimmed.split('.').next().unwrap_or(trimmed).trim_end();
+    if RESERVED_NAMES
+        .iter()
+        .any(|name| stem.eq_ignore_ascii_case(name))
+    {
+        return Err(GamePathError::ReservedName(raw.to_string()));
+    }
+
+    Ok(Some(trimmed.to_string()))
+}
+
+fn has_drive_prefix(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root() -> PathBuf {
+        PathBuf::from("install").join("games")
+    }
+
+    #[test]
+    fn builds_nested_path_under_app_folder() {
+        let game = GamePath::new(&root(), "123", "dota 2 beta\\game/bin", "dota2.exe").unwrap();
+        let folder = root()
+            .join("123")
+            .join("dota 2 beta")
+            .join("game")
+            .join("bin");
+        assert_eq!(game.folder(), folder);
+        assert_eq!(game.executable(), folder.join("dota2.exe"));
+    }
+
+    #[test]
+    fn empty_path_means_app_folder() {
+        let game = GamePath::new(&root(), "123", "", "game.exe").unwrap();
+        assert_eq!(game.folder(), root().join("123"));
+    }
+
+    #[test]
+    fn normalizes_dot_and_empty_components() {
+        let game = GamePath::new(&root(), "123", "./bin//win64/.", "game.exe").unwrap();
+        assert_eq!(game.folder(), root().join("123").join("bin").join("win64"));
+    }
+
+    #[test]
+    fn rejects_parent_traversal() {
+        for path in [
+            "..",
+            "../..",
+            "bin/../../x",
+            "bin\\..\\..",
+            ".. ",
+            "bin/.../x",
+        ] {
+            assert!(
+                matches!(
+                    GamePath::new(&root(), "123", path, "game.exe"),
+                    Err(GamePathError::Traversal(_))
+                ),
+                "{path}"
+            );
+        }
+        assert!(GamePath::new(&root(), "..", "", "game.exe").is_err());
+        assert!(GamePath::new(&root(), "123", "", "..").is_err());
+    }
+
+    #[test]
+    fn rejects_absolute_paths() {
+        for path in [
+            "/etc",
+            "\\Windows",
+            "C:\\Windows",
+            "c:relative",
+            "C:/Windows",
+        ] {
+            assert!(
+                matches!(
+                    GamePath::new(&root(), "123", path, "game.exe"),
+                    Err(GamePathError::Absolute(_))
+                ),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unc_paths() {
+        for path in ["\\\\server\\share", "//server/share", "\\\\?\\C:\\Windows"] {
+            assert!(
+                matches!(
+                    GamePath::new(&root(), "123", path, "game.exe"),
+                    Err(GamePathError::Absolute(_))
+                ),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_reserved_names() {
+        for name in ["CON", "nul", "com1.exe", "Lpt9.txt", "aux "] {
+            assert!(
+                matches!(
+                    GamePath::new(&root(), "123", "", name),
+                    Err(GamePathError::ReservedName(_))
+                ),
+                "{name}"
+            );
+        }
+        assert!(GamePath::new(&root(), "123", "bin/con", "game.exe").is_err());
+        assert!(matches!(
+            GamePath::new(&root(), ".stubs", "", "game.exe"),
+            Err(GamePathError::ReservedName(_))
+        ));
+        assert!(GamePath::new(&root(), "123", "console", "conhost.exe").is_ok());
+    }
+
+    #[test]
+    fn rejects_illegal_characters() {
+        assert!(GamePath::new(&root(), "123", "bin:stream", "game.exe").is_err());
+        assert!(GamePath::new(&root(), "123", "", "game?.exe").is_err());
+        assert!(GamePath::new(&root(), "123", "bin\0", "game.exe").is_err());
+    }
+
+    #[test]
+    fn games_root_sits_next_to_the_executable() {
+        let exe = PathBuf::from("install").join("DiscordQuest.exe");
+        assert_eq!(games_root_for(&exe), root());
+        assert!(!is_app_folder(STORE_DIR));
+    }
+
+    #[test]
+    fn usage_key_is_relative_and_slash_separated() {
+        let exe = root().join("1").join("bin").join("game.exe");
+        assert_eq!(usage_key(&root(), &exe), "1/bin/game.exe");
+    }
+
+    #[test]
+    fn file_names_must_be_single_components() {
+        assert!(matches!(
+            GamePath::new(&root(), "123", "", "bin/game.exe"),
+            Err(GamePathError::NotAFileName(_))
+        ));
+        assert!(matches!(
+            GamePath::new(&root(), "1/2", "", "game.exe"),
+            Err(GamePathError::NotAFileName(_))
+        ));
+        assert!(matches!(
+            GamePath::new(&root(), "", "", "game.exe"),
+            Err(GamePathError::Empty("app_id"))
+        ));
+    }
+}
@@ -0,0 +1,14 @@
+//! The contract between the app and the `src-win` stubs, shared by both.
+//!
+//! - [`stub_args`]: the stub's command line.
+//! - [`process_key`]: how a running stub is identified.
+//! - [`events`]: what the webview is told about stubs.
+//! - [`game_path`]: where stub executables live under the games root.
+
+pub mod events;
+pub mod game_path;
+pub mod process_key;
+pub mod stub_args;
+
+pub use process_key::ProcessKey;
+pub use stub_args::StubArgs;
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Identifies a running stub: one per executable of a game.
+///
+/// Written as `app_id:executable_name` wherever a string is needed, e.g. in
+/// `processes.json` and in what the webview receives. App ids are Discord
+/// snowflakes and never contain `:`, so the first `:` splits the two parts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProcessKey {
+    pub app_id: String,
+    pub executable_name: String,
+}
+
+impl ProcessKey {
+    pub fn new(app_id: &str, executable_name: &str) -> Self {
+        ProcessKey {
+            app_id: app_id.to_string(),
+            executable_name: executable_name.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ProcessKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.app_id, self.executable_name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidProcessKey(pub String);
+
+impl fmt::Display for InvalidProcessKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid process key: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidProcessKey {}
+
+impl FromStr for ProcessKey {
+    type Err = InvalidProcessKey;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once(':') {
+            Some((app_id, executable_name))
+                if !app_id.is_empty() && !executable_name.is_empty() =>
+            {
+                Ok(ProcessKey::new(app_id, executable_name))
+            }
+            _ => Err(InvalidProcessKey(value.to_string())),
+        }
+    }
+}
+
+impl Serialize for ProcessKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ProcessKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_as_a_string() {
+        let key = ProcessKey::new("356875570916753438", "game:v2.exe");
+        assert_eq!(key.to_string(), "356875570916753438:game:v2.exe");
+        assert_eq!(key.to_string().parse::<ProcessKey>().unwrap(), key);
+
+        let json = serde_json::to_value(&key).unwrap();
+        assert_eq!(json, "356875570916753438:game:v2.exe");
+        assert_eq!(serde_json::from_value::<ProcessKey>(json).unwrap(), key);
+    }
+
+    #[test]
+    fn rejects_keys_missing_a_part() {
+        for value in ["", "123", ":game.exe", "123:"] {
+            assert!(value.parse::<ProcessKey>().is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Command line of the `src-win` stub. The app builds it with
+//! [`StubArgs::to_args`] and the stub reads it back with [`StubArgs::parse`].
+
+use discord_quest_i18n::Lang;
+use std::time::Duration;
+
+pub const DEFAULT_TITLE: &str = "DiscordQuest";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StubArgs {
+    pub title: String,
+    pub lang: Lang,
+    /// Close when this process (the app that launched us) is gone.
+    pub parent_pid: Option<u32>,
+    /// Close after running this long.
+    pub max_runtime: Option<Duration>,
+    /// Start with only the tray icon; the window shows when asked from the tray or the app.
+    pub start_minimized: bool,
+}
+
+impl Default for StubArgs {
+    fn default() -> Self {
+        StubArgs {
+            title: DEFAULT_TITLE.to_string(),
+            lang: Lang::default(),
+            parent_pid: None,
+            max_runtime: None,
+            start_minimized: false,
+        }
+    }
+}
+
+impl StubArgs {
+    pub fn new(title: &str) -> Self {
+        StubArgs {
+            title: title.to_string(),
+            ..StubArgs::default()
+        }
+    }
+
+    /// Arguments after the program name. Optional values are left out when unset.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--title".to_string(),
+            self.title.clone(),
+            "--lang".to_string(),
+            self.lang.code().to_string(),
+        ];
+        if let Some(pid) = self.parent_pid {
+            args.extend(["--parent-pid".to_string(), pid.to_string()]);
+        }
+        if let Some(max_runtime) = self.max_runtime {
+            args.extend([
+                "--max-runtime".to_string(),
+                max_runtime.as_secs().to_string(),
+            ]);
+        }
+        if self.start_minimized {
+            args.push("--tray".to_string());
+        }
+        args
+    }
+
+    /// Parses the arguments after the program name. Unknown flags and bad values
+    /// are ignored so an older or newer app can still start the stub.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Self {
+        let mut parsed = StubArgs::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--title" => {
+                    if let Some(title) = args.next() {
+                        parsed.title = title;
+                    }
+                }
+                "--lang" => {
+                    if let Some(lang) = args.next().and_then(|value| Lang::from_code(&value)) {
+                        parsed.lang = lang;
+                    }
+                }
+                "--parent-pid" => {
+                    parsed.parent_pid = args.next().and_then(|value| value.parse().ok());
+                }
+                "--max-runtime" => {
+                    parsed.max_runtime = args.next().and_then(|value| parse_duration(&value));
+                }
+                "--tray" => parsed.start_minimized = true,
+                _ => {}
+            }
+        }
+
+        parsed
+    }
+}
+
+/// `90`, `90s`, `45m`, `8h` or `2d`. Zero is treated as no limit.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(split) => value.split_at(split),
+        None => (value, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    let secs = number.parse::<u64>().ok()?.checked_mul(multiplier)?;
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> StubArgs {
+        StubArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn round_trips_through_the_command_line() {
+        let full = StubArgs {
+            title: "Honkai: Star Rail --lang".to_string(),
+            lang: Lang::En,
+            parent_pid: Some(4321),
+            max_runtime: Some(Duration::from_secs(7200)),
+            start_minimized: true,
+        };
+        for args in [full, StubArgs::new("Game"), StubArgs::new("")] {
+            assert_eq!(StubArgs::parse(args.to_args()), args);
+        }
+    }
+
+    #[test]
+    fn reads_suffixed_durations() {
+        assert_eq!(
+            parse(&["--title", "Juego", "--max-runtime", "2h"]).max_runtime,
+            Some(Duration::from_secs(7200))
+        );
+    }
+
+    #[test]
+    fn bad_or_missing_values_fall_back_to_defaults() {
+        assert_eq!(
+            parse(&[
+                "--lang",
+                "xx",
+                "--parent-pid",
+                "abc",
+                "--max-runtime",
+                "soon",
+                "--unknown",
+                "--title"
+            ]),
+            StubArgs::default()
+        );
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("45m"), Some(Duration::from_secs(2700)));
+        assert_eq!(parse_duration("2d"), Some(Duration::from_secs(172_800)));
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("5w"), None);
+        assert_eq!(parse_duration("m"), None);
+    }
+}
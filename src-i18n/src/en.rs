@@ -0,0 +1,80 @@
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("error.invalid_path", "Invalid path: {}"),
+    (
+        "error.dir_create_failed",
+        "Could not create the game folder: {}",
+    ),
+    ("error.resource_missing", "Resource not found: {}"),
+    ("error.copy_failed", "Could not copy the executable: {}"),
+    ("error.spawn_failed", "Could not start the process: {}"),
+    (
+        "error.limit_reached",
+        "{} games are already running, the configured maximum",
+    ),
+    ("error.already_running", "The game is already running: {}"),
+    ("error.not_running", "The process is not running: {}"),
+    (
+        "error.not_owned",
+        "No {} process was started from the games folder",
+    ),
+    ("error.invalid_settings", "Invalid settings: {}"),
+    (
+        "error.settings_save_failed",
+        "Could not save the settings: {}",
+    ),
+    (
+        "error.inventory_failed",
+        "Could not read the games folder: {}",
+    ),
+    (
+        "error.stub_unreachable",
+        "Could not reach the game window: {}",
+    ),
+    ("error.history_failed", "Session history error: {}"),
+    ("error.invalid_catalog", "The game list is not valid: {}"),
+    (
+        "error.catalog_unavailable",
+        "Could not download the game list and there is no saved copy: {}",
+    ),
+    (
+        "error.catalog_not_loaded",
+        "The game list has not been loaded yet",
+    ),
+    (
+        "settings.games_dir_not_absolute",
+        "The games folder must be an absolute path: {}",
+    ),
+    (
+        "settings.games_dir_in_use",
+        "The games folder must be empty or one this app already uses: {}",
+    ),
+    (
+        "settings.stop_grace_out_of_range",
+        "The stop grace period ({} ms) must be between {} and {} ms",
+    ),
+    ("path.empty", "The '{}' field is empty"),
+    ("path.absolute", "Absolute paths are not allowed: {}"),
+    (
+        "path.traversal",
+        "The path cannot leave the games folder: {}",
+    ),
+    (
+        "path.illegal_character",
+        "The path contains invalid characters: {}",
+    ),
+    ("path.reserved_name", "Name reserved by the system: {}"),
+    (
+        "path.not_a_file_name",
+        "Expected a file name, not a path: {}",
+    ),
+    ("path.outside_root", "The resulting path is outside games/"),
+    ("stub.active", "* Active"),
+    ("stub.status_label", "STATUS"),
+    ("stub.status_value", "Simulating"),
+    ("stub.kind_label", "TYPE"),
+    ("stub.kind_value", "Quest Runner"),
+    ("stub.elapsed_label", "TIME ACTIVE"),
+    ("stub.footer", "DiscordQuest - Do not close this window"),
+    ("stub.tray_show", "Show"),
+    ("stub.tray_close", "Close"),
+];
@@ -0,0 +1,89 @@
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("error.invalid_path", "Ruta no válida: {}"),
+    (
+        "error.dir_create_failed",
+        "Error al crear carpeta del juego: {}",
+    ),
+    ("error.resource_missing", "No se encontró el recurso: {}"),
+    ("error.copy_failed", "Error al copiar ejecutable: {}"),
+    ("error.spawn_failed", "Error al iniciar proceso: {}"),
+    (
+        "error.limit_reached",
+        "Ya hay {} juegos en ejecución, el máximo configurado",
+    ),
+    ("error.already_running", "El juego ya está en ejecución: {}"),
+    ("error.not_running", "El proceso no está en ejecución: {}"),
+    (
+        "error.not_owned",
+        "No hay ningún proceso {} iniciado desde la carpeta de juegos",
+    ),
+    ("error.invalid_settings", "Configuración no válida: {}"),
+    (
+        "error.settings_save_failed",
+        "Error al guardar la configuración: {}",
+    ),
+    (
+        "error.inventory_failed",
+        "Error al leer la carpeta de juegos: {}",
+    ),
+    (
+        "error.stub_unreachable",
+        "No se pudo comunicar con la ventana del juego: {}",
+    ),
+    (
+        "error.history_failed",
+        "Error en el historial de sesiones: {}",
+    ),
+    (
+        "error.invalid_catalog",
+        "La lista de juegos no es válida: {}",
+    ),
+    (
+        "error.catalog_unavailable",
+        "No se pudo descargar la lista de juegos y no hay copia guardada: {}",
+    ),
+    (
+        "error.catalog_not_loaded",
+        "La lista de juegos todavía no se ha cargado",
+    ),
+    (
+        "settings.games_dir_not_absolute",
+        "La carpeta de juegos debe ser una ruta absoluta: {}",
+    ),
+    (
+        "settings.games_dir_in_use",
+        "La carpeta de juegos debe estar vacía o ser una que ya usa la app: {}",
+    ),
+    (
+        "settings.stop_grace_out_of_range",
+        "El tiempo de cierre ({} ms) debe estar entre {} y {} ms",
+    ),
+    ("path.empty", "El campo '{}' está vacío"),
+    ("path.absolute", "Ruta absoluta no permitida: {}"),
+    (
+        "path.traversal",
+        "La ruta no puede salir de la carpeta de juegos: {}",
+    ),
+    (
+        "path.illegal_character",
+        "La ruta contiene caracteres no válidos: {}",
+    ),
+    ("path.reserved_name", "Nombre reservado por el sistema: {}"),
+    (
+        "path.not_a_file_name",
+        "Se esperaba un nombre de archivo, no una ruta: {}",
+    ),
+    (
+        "path.outside_root",
+        "La ruta resultante queda fuera de games/",
+    ),
+    ("stub.active", "* Activo"),
+    ("stub.status_label", "ESTADO"),
+    ("stub.status_value", "Simulando"),
+    ("stub.kind_label", "TIPO"),
+    ("stub.kind_value", "Quest Runner"),
+    ("stub.elapsed_label", "TIEMPO ACTIVO"),
+    ("stub.footer", "DiscordQuest - No cerrar esta ventana"),
+    ("stub.tray_show", "Mostrar"),
+    ("stub.tray_close", "Cerrar"),
+];
@@ -0,0 +1,137 @@
+//! User-facing text for the app backend and the `src-win` stub.
+//!
+//! Each language is a table of `key -> message` in its own module. Messages
+//! take positional `{}` placeholders filled by [`format`]. A key missing from
+//! a language falls back to Spanish, the language the app was written in.
+
+mod en;
+mod es;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    Es,
+    En,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::Es, Lang::En];
+
+    /// The code passed to the stub with `--lang`.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::Es => "es",
+            Lang::En => "en",
+        }
+    }
+
+    /// Accepts `es`, `en` and regional forms such as `en-US` or `es_MX`.
+    pub fn from_code(code: &str) -> Option<Lang> {
+        let primary = code.split(['-', '_']).next()?.to_ascii_lowercase();
+        Lang::ALL.into_iter().find(|lang| lang.code() == primary)
+    }
+
+    fn messages(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::Es => es::MESSAGES,
+            Lang::En => en::MESSAGES,
+        }
+    }
+}
+
+fn lookup(lang: Lang, key: &str) -> Option<&'static str> {
+    lang.messages()
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, message)| *message)
+}
+
+/// The message for `key`, or the key itself if no language has it.
+pub fn text(lang: Lang, key: &'static str) -> &'static str {
+    lookup(lang, key)
+        .or_else(|| lookup(Lang::default(), key))
+        .unwrap_or(key)
+}
+
+/// The message for `key` with each `{}` replaced by the next argument.
+pub fn format(lang: Lang, key: &'static str, args: &[&dyn fmt::Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    for (i, part) in text(lang, key).split("{}").enumerate() {
+        if i > 0 {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string());
+            }
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn keys(lang: Lang) -> HashSet<&'static str> {
+        lang.messages().iter().map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn every_key_exists_in_every_language() {
+        let all: HashSet<&str> = Lang::ALL.into_iter().flat_map(keys).collect();
+        for lang in Lang::ALL {
+            let present = keys(lang);
+            let mut missing: Vec<_> = all.difference(&present).collect();
+            missing.sort();
+            assert!(missing.is_empty(), "{:?} is missing {:?}", lang, missing);
+        }
+    }
+
+    #[test]
+    fn no_key_is_defined_twice() {
+        for lang in Lang::ALL {
+            assert_eq!(keys(lang).len(), lang.messages().len(), "{:?}", lang);
+        }
+    }
+
+    #[test]
+    fn translations_take_the_same_arguments() {
+        for (key, message) in es::MESSAGES {
+            for lang in Lang::ALL {
+                assert_eq!(
+                    text(lang, key).matches("{}").count(),
+                    message.matches("{}").count(),
+                    "{:?} {}",
+                    lang,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn formats_in_the_requested_language() {
+        assert_eq!(
+            format(Lang::Es, "error.not_running", &[&"1:game.exe"]),
+            "El proceso no está en ejecución: 1:game.exe"
+        );
+        assert_eq!(
+            format(Lang::En, "error.not_owned", &[&"game.exe"]),
+            "No game.exe process was started from the games folder"
+        );
+        assert_eq!(text(Lang::En, "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn language_codes() {
+        assert_eq!(Lang::from_code("en-US"), Some(Lang::En));
+        assert_eq!(Lang::from_code("ES_mx"), Some(Lang::Es));
+        assert_eq!(Lang::from_code("fr"), None);
+        assert_eq!(serde_json::to_value(Lang::En).unwrap(), "en");
+    }
+}
@@ -0,0 +1,105 @@
+use crate::endpoint;
+use crate::protocol::{decode, encode, Event, Message, Request, Response};
+use interprocess::local_socket::{prelude::*, Stream};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const CONNECT_RETRY: Duration = Duration::from_millis(50);
+/// How long a request may wait for the stub's answer.
+pub const RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// App side of one connection to a stub.
+///
+/// Requests block until the stub answers. On Unix the wait is bounded by
+/// [`RESPONSE_TIMEOUT`]; named pipes have no read timeout, so on Windows a stub
+/// whose IPC thread is stuck blocks the caller forever. Callers that must not
+/// hang run the request on a thread of their own and stop waiting after
+/// [`RESPONSE_TIMEOUT`].
+pub struct Client {
+    reader: BufReader<Stream>,
+    next_id: u64,
+    /// Events that arrived while waiting for a response.
+    events: VecDeque<Event>,
+}
+
+impl Client {
+    /// Connects to the stub running as `pid`, retrying for up to `wait` while
+    /// it may still be starting its listener. `Duration::ZERO` tries once.
+    pub fn connect(pid: u32, wait: Duration) -> io::Result<Client> {
+        let deadline = Instant::now() + wait;
+        loop {
+            match Stream::connect(endpoint(pid)?) {
+                Ok(stream) => {
+                    let _ = stream.set_recv_timeout(Some(RESPONSE_TIMEOUT));
+                    return Ok(Client {
+                        reader: BufReader::new(stream),
+                        next_id: 1,
+                        events: VecDeque::new(),
+                    });
+                }
+                Err(e) if is_not_listening(&e) && Instant::now() < deadline => {
+                    thread::sleep(CONNECT_RETRY);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn request(&mut self, request: Request) -> io::Result<Response> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.reader
+            .get_mut()
+            .write_all(&encode(&Message::Request { id, request }))?;
+
+        loop {
+            match self.read_message()? {
+                Message::Response {
+                    id: answered,
+                    response,
+                } if answered == id => return Ok(response),
+                Message::Event { event } => self.events.push_back(event),
+                _ => {}
+            }
+        }
+    }
+
+    /// Blocks until the stub sends an event. Fails once the stub has exited.
+    pub fn next_event(&mut self) -> io::Result<Event> {
+        let _ = self.reader.get_ref().set_recv_timeout(None);
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+            if let Message::Event { event } = self.read_message()? {
+                return Ok(event);
+            }
+        }
+    }
+
+    fn read_message(&mut self) -> io::Result<Message> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the stub closed the connection",
+                ));
+            }
+            if !line.trim().is_empty() {
+                return decode(line.trim_end())
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e));
+            }
+        }
+    }
+}
+
+fn is_not_listening(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+    )
+}
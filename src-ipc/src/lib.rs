@@ -0,0 +1,226 @@
+//! Control channel between the app and each running `src-win` stub.
+//!
+//! Every stub listens on its own local socket, named after its PID: a Unix
+//! domain socket in a directory only the current user can enter, or a named
+//! pipe only the current user can open on Windows. The app
+//! connects to it to send [`Request`]s, and the stub pushes [`Event`]s to every
+//! open connection. Frames are newline-delimited JSON, see [`protocol`].
+
+mod client;
+pub mod protocol;
+#[cfg(windows)]
+mod security;
+mod server;
+
+pub use client::{Client, RESPONSE_TIMEOUT};
+pub use protocol::{Event, Request, Response, StubStatus, PROTOCOL_VERSION};
+pub use server::{Handler, Server};
+
+use interprocess::local_socket::Name;
+use std::io;
+
+/// Where the sockets live: `$XDG_RUNTIME_DIR/discord-quest`, or a
+/// `discord-quest-<uid>` folder in the temp directory when that is not set.
+#[cfg(unix)]
+fn socket_dir() -> io::Result<std::path::PathBuf> {
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime) if !runtime.is_empty() => {
+            std::path::PathBuf::from(runtime).join("discord-quest")
+        }
+        // SAFETY: getuid(2) cannot fail and has no memory-safety requirements
+        _ => std::env::temp_dir().join(format!("discord-quest-{}", unsafe { libc::getuid() })),
+    };
+    ensure_private_dir(&dir)?;
+    Ok(dir)
+}
+
+/// Creates `dir` with mode 0700, or checks that an existing one is a real
+/// directory owned by us that nobody else can enter. The temp directory is
+/// shared, so another user could have created it first to hijack the sockets.
+#[cfg(unix)]
+fn ensure_private_dir(dir: &std::path::Path) -> io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e),
+    }
+    let metadata = std::fs::symlink_metadata(dir)?;
+    // SAFETY: getuid(2) cannot fail and has no memory-safety requirements
+    let private = metadata.is_dir()
+        && metadata.uid() == unsafe { libc::getuid() }
+        && metadata.permissions().mode() & 0o077 == 0;
+    if private {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a private directory", dir.display()),
+        ))
+    }
+}
+
+#[cfg(unix)]
+fn socket_path(pid: u32) -> io::Result<std::path::PathBuf> {
+    Ok(socket_dir()?.join(format!("{}.sock", pid)))
+}
+
+fn endpoint(pid: u32) -> io::Result<Name<'static>> {
+    #[cfg(unix)]
+    {
+        use interprocess::local_socket::{GenericFilePath, ToFsName};
+        socket_path(pid)?.to_fs_name::<GenericFilePath>()
+    }
+    #[cfg(windows)]
+    {
+        use interprocess::local_socket::{GenericNamespaced, ToNsName};
+        format!("discord-quest-{}", pid).to_ns_name::<GenericNamespaced>()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    const WAIT: Duration = Duration::from_secs(2);
+
+    /// PIDs far above any real one, unique per test so endpoints never collide.
+    fn fake_pid() -> u32 {
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        4_000_000_000 + std::process::id() % 10_000 * 100 + NEXT.fetch_add(1, Ordering::Relaxed)
+    }
+
+    #[derive(Default)]
+    struct FakeStub {
+        received: Arc<Mutex<Vec<Request>>>,
+    }
+
+    impl Handler for FakeStub {
+        fn handle(&self, request: Request) -> Response {
+            self.received.lock().unwrap().push(request.clone());
+            match request {
+                Request::Ping => Response::Pong,
+                Request::GetStatus => Response::Status(StubStatus {
+                    pid: 1234,
+                    title: "Juego".to_string(),
+                    elapsed_secs: 90,
+                }),
+                Request::Close | Request::ShowWindow => Response::Done,
+            }
+        }
+    }
+
+    #[test]
+    fn client_and_stub_exchange_requests() {
+        let pid = fake_pid();
+        let stub = FakeStub::default();
+        let received = Arc::clone(&stub.received);
+        let _server = Server::start(pid, stub).unwrap();
+
+        let mut client = Client::connect(pid, WAIT).unwrap();
+        assert_eq!(client.request(Request::Ping).unwrap(), Response::Pong);
+        assert_eq!(
+            client.request(Request::GetStatus).unwrap(),
+            Response::Status(StubStatus {
+                pid: 1234,
+                title: "Juego".to_string(),
+                elapsed_secs: 90,
+            })
+        );
+        assert_eq!(client.request(Request::Close).unwrap(), Response::Done);
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![Request::Ping, Request::GetStatus, Request::Close]
+        );
+    }
+
+    #[test]
+    fn events_reach_every_connection() {
+        let pid = fake_pid();
+        let server = Server::start(pid, FakeStub::default()).unwrap();
+        let mut watcher = Client::connect(pid, WAIT).unwrap();
+        let mut other = Client::connect(pid, WAIT).unwrap();
+        // Make sure both connections are registered before broadcasting
+        watcher.request(Request::Ping).unwrap();
+        other.request(Request::Ping).unwrap();
+
+        server.broadcast(Event::TrayClosed);
+
+        assert_eq!(watcher.next_event().unwrap(), Event::TrayClosed);
+        // An event that arrives before a response is kept for later
+        assert_eq!(other.request(Request::Ping).unwrap(), Response::Pong);
+        assert_eq!(other.next_event().unwrap(), Event::TrayClosed);
+    }
+
+    #[test]
+    fn newer_peers_get_an_error_response() {
+        let pid = fake_pid();
+        let _server = Server::start(pid, FakeStub::default()).unwrap();
+        let mut stream = {
+            use interprocess::local_socket::{prelude::*, Stream};
+            Stream::connect(endpoint(pid).unwrap()).unwrap()
+        };
+        stream
+            .write_all(
+                b"{\"version\":99,\"kind\":\"request\",\"id\":3,\"request\":{\"type\":\"ping\"}}\n",
+            )
+            .unwrap();
+
+        let mut line = String::new();
+        BufReader::new(&mut stream).read_line(&mut line).unwrap();
+        match protocol::decode(line.trim_end()).unwrap() {
+            protocol::Message::Response {
+                id: 3,
+                response: Response::Error { .. },
+            } => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn connect_gives_up_when_nobody_listens() {
+        let error = Client::connect(fake_pid(), Duration::ZERO).err().unwrap();
+        assert!(matches!(
+            error.kind(),
+            std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+        ));
+    }
+
+    #[test]
+    fn dropping_the_server_removes_the_socket() {
+        let pid = fake_pid();
+        let server = Server::start(pid, FakeStub::default()).unwrap();
+        assert!(socket_path(pid).unwrap().exists());
+        drop(server);
+        assert!(!socket_path(pid).unwrap().exists());
+    }
+
+    #[test]
+    fn socket_dir_must_be_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let base = std::env::temp_dir().join(format!("discord-quest-test-{}", fake_pid()));
+        let dir = base.join("sockets");
+        std::fs::create_dir_all(&base).unwrap();
+
+        ensure_private_dir(&dir).unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        ensure_private_dir(&dir).unwrap();
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let error = ensure_private_dir(&dir).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+
+        let link = base.join("link");
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        assert!(ensure_private_dir(&link).is_err());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Bumped on any change that an older peer would misread.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Sent by the app; every request gets exactly one [`Response`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    Ping,
+    GetStatus,
+    /// Close the window as if the user did, ending the stub.
+    Close,
+    /// Restore the window and bring it to the front.
+    ShowWindow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    Pong,
+    Status(StubStatus),
+    /// The request was accepted.
+    Done,
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StubStatus {
+    pub pid: u32,
+    pub title: String,
+    pub elapsed_secs: u64,
+}
+
+/// Sent by the stub on its own, to every open connection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// The user picked "Cerrar" in the tray menu; the stub is exiting.
+    TrayClosed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Message {
+    Request { id: u64, request: Request },
+    Response { id: u64, response: Response },
+    Event { event: Event },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Frame {
+    version: u32,
+    #[serde(flatten)]
+    message: Message,
+}
+
+/// Read before the full frame so a newer peer is reported as such rather than as garbage.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+    id: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    Malformed(String),
+    /// The peer speaks another version; `id` is set if the frame was a request.
+    UnsupportedVersion {
+        version: u32,
+        id: Option<u64>,
+    },
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::Malformed(e) => write!(f, "Malformed message: {}", e),
+            ProtocolError::UnsupportedVersion { version, .. } => write!(
+                f,
+                "Unsupported protocol version {} (expected {})",
+                version, PROTOCOL_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// One frame, newline included.
+pub fn encode(message: &Message) -> Vec<u8> {
+    let frame = Frame {
+        version: PROTOCOL_VERSION,
+        message: message.clone(),
+    };
+    let mut line = serde_json::to_vec(&frame).expect("protocol types always serialize");
+    line.push(b'\n');
+    line
+}
+
+pub fn decode(line: &str) -> Result<Message, ProtocolError> {
+    let header: Header =
+        serde_json::from_str(line).map_err(|e| ProtocolError::Malformed(e.to_string()))?;
+    if header.version != PROTOCOL_VERSION {
+        return Err(ProtocolError::UnsupportedVersion {
+            version: header.version,
+            id: header.id,
+        });
+    }
+    serde_json::from_str::<Frame>(line)
+        .map(|frame| frame.message)
+        .map_err(|e| ProtocolError::Malformed(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(message: Message) {
+        let line = encode(&message);
+        assert_eq!(line.last(), Some(&b'\n'));
+        let text = std::str::from_utf8(&line).unwrap();
+        assert_eq!(decode(text.trim_end()).unwrap(), message);
+    }
+
+    #[test]
+    fn every_message_round_trips() {
+        for request in [
+            Request::Ping,
+            Request::GetStatus,
+            Request::Close,
+            Request::ShowWindow,
+        ] {
+            round_trip(Message::Request { id: 7, request });
+        }
+        for response in [
+            Response::Pong,
+            Response::Done,
+            Response::Status(StubStatus {
+                pid: 42,
+                title: "Juego \"con\" comillas\ny saltos".to_string(),
+                elapsed_secs: 3600,
+            }),
+            Response::Error {
+                message: "fallo".to_string(),
+            },
+        ] {
+            round_trip(Message::Response { id: 7, response });
+        }
+        round_trip(Message::Event {
+            event: Event::TrayClosed,
+        });
+    }
+
+    #[test]
+    fn wire_format_is_flat_json() {
+        let line = encode(&Message::Request {
+            id: 1,
+            request: Request::GetStatus,
+        });
+        assert_eq!(
+            std::str::from_utf8(&line).unwrap(),
+            "{\"version\":1,\"kind\":\"request\",\"id\":1,\"request\":{\"type\":\"get_status\"}}\n"
+        );
+    }
+
+    #[test]
+    fn other_versions_are_rejected_with_their_id() {
+        let line = r#"{"version":2,"kind":"request","id":5,"request":{"type":"teleport"}}"#;
+        assert_eq!(
+            decode(line),
+            Err(ProtocolError::UnsupportedVersion {
+                version: 2,
+                id: Some(5)
+            })
+        );
+        assert!(matches!(
+            decode("not json"),
+            Err(ProtocolError::Malformed(_))
+        ));
+        assert!(matches!(
+            decode(r#"{"version":1,"kind":"request","id":5,"request":{"type":"teleport"}}"#),
+            Err(ProtocolError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,61 @@
+//! Access control for the stub's named pipe.
+
+use interprocess::os::windows::security_descriptor::SecurityDescriptor;
+use std::io;
+use widestring::U16CString;
+use windows_sys::core::PWSTR;
+use windows_sys::Win32::Foundation::{CloseHandle, LocalFree, HANDLE};
+use windows_sys::Win32::Security::Authorization::ConvertSidToStringSidW;
+use windows_sys::Win32::Security::{GetTokenInformation, TokenUser, TOKEN_QUERY, TOKEN_USER};
+use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+/// A descriptor whose only entry gives the current user full access, so other
+/// accounts on the machine can neither talk to a stub nor pose as one.
+pub fn current_user_only() -> io::Result<SecurityDescriptor> {
+    let sddl = format!("D:P(A;;GA;;;{})", current_user_sid()?);
+    let sddl = U16CString::from_str(sddl).map_err(io::Error::other)?;
+    SecurityDescriptor::deserialize(&sddl)
+}
+
+/// The SID of the user running this process, as an `S-1-5-...` string.
+fn current_user_sid() -> io::Result<String> {
+    let mut token: HANDLE = std::ptr::null_mut();
+    // SAFETY: the pseudo handle of the current process is always valid
+    if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let sid = token_user_sid(token);
+    // SAFETY: `token` was opened above and is not used afterwards
+    unsafe { CloseHandle(token) };
+    sid
+}
+
+fn token_user_sid(token: HANDLE) -> io::Result<String> {
+    let mut len = 0u32;
+    // SAFETY: a null buffer of length 0 only asks for the needed size
+    unsafe { GetTokenInformation(token, TokenUser, std::ptr::null_mut(), 0, &mut len) };
+    // u64 items keep the buffer aligned for TOKEN_USER
+    let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+    // SAFETY: `buffer` holds at least `len` writable bytes
+    let ok =
+        unsafe { GetTokenInformation(token, TokenUser, buffer.as_mut_ptr().cast(), len, &mut len) };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: on success the buffer starts with a TOKEN_USER whose SID points into it
+    let sid = unsafe { (*buffer.as_ptr().cast::<TOKEN_USER>()).User.Sid };
+
+    let mut text: PWSTR = std::ptr::null_mut();
+    // SAFETY: `sid` is valid while `buffer` lives; `text` receives a LocalAlloc'd string
+    if unsafe { ConvertSidToStringSidW(sid, &mut text) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // SAFETY: `text` is NUL-terminated and freed once copied
+    let sid = unsafe {
+        let len = (0..).take_while(|&i| *text.add(i) != 0).count();
+        let sid = String::from_utf16_lossy(std::slice::from_raw_parts(text, len));
+        LocalFree(text.cast());
+        sid
+    };
+    Ok(sid)
+}
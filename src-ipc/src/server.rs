@@ -0,0 +1,125 @@
+use crate::endpoint;
+use crate::protocol::{decode, encode, Event, Message, ProtocolError, Request, Response};
+use interprocess::local_socket::{prelude::*, ListenerOptions, Stream};
+use std::io::{self, BufRead, BufReader, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Answers requests on the stub side. Called from IPC threads, never the UI thread.
+pub trait Handler: Send + Sync + 'static {
+    fn handle(&self, request: Request) -> Response;
+}
+
+struct Connection {
+    stream: Stream,
+    /// Keeps a response and a broadcast event from interleaving on the wire.
+    writing: Mutex<()>,
+}
+
+impl Connection {
+    fn send(&self, message: &Message) -> io::Result<()> {
+        let _guard = self.writing.lock().unwrap();
+        (&self.stream).write_all(&encode(message))
+    }
+}
+
+/// Listening end owned by the stub. Each connection is served on its own thread.
+pub struct Server {
+    connections: Arc<Mutex<Vec<Arc<Connection>>>>,
+    #[cfg(unix)]
+    path: std::path::PathBuf,
+}
+
+impl Server {
+    /// Starts listening on the endpoint for `pid`, normally `std::process::id()`.
+    /// A stale socket left by a dead process with the same PID is replaced.
+    pub fn start(pid: u32, handler: impl Handler) -> io::Result<Server> {
+        let options = ListenerOptions::new()
+            .name(endpoint(pid)?)
+            .try_overwrite(true);
+        #[cfg(windows)]
+        let options = {
+            use interprocess::os::windows::local_socket::ListenerOptionsExt;
+            options.security_descriptor(crate::security::current_user_only()?)
+        };
+        let listener = options.create_sync()?;
+        let connections: Arc<Mutex<Vec<Arc<Connection>>>> = Arc::default();
+        let handler = Arc::new(handler);
+
+        let accepted = Arc::clone(&connections);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let connection = Arc::new(Connection {
+                    stream,
+                    writing: Mutex::new(()),
+                });
+                accepted.lock().unwrap().push(Arc::clone(&connection));
+
+                let handler = Arc::clone(&handler);
+                let accepted = Arc::clone(&accepted);
+                thread::spawn(move || {
+                    serve(&connection, &*handler);
+                    accepted
+                        .lock()
+                        .unwrap()
+                        .retain(|other| !Arc::ptr_eq(other, &connection));
+                });
+            }
+        });
+
+        Ok(Server {
+            connections,
+            #[cfg(unix)]
+            path: crate::socket_path(pid)?,
+        })
+    }
+
+    /// Sends `event` to every open connection. Returns once it is written.
+    pub fn broadcast(&self, event: Event) {
+        let message = Message::Event { event };
+        let connections = self.connections.lock().unwrap().clone();
+        for connection in connections {
+            let _ = connection.send(&message);
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn serve(connection: &Connection, handler: &dyn Handler) {
+    let reader = BufReader::new(&connection.stream);
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (id, response) = match decode(&line) {
+            Ok(Message::Request { id, request }) => (id, handler.handle(request)),
+            // Only requests flow towards the stub
+            Ok(_) => continue,
+            Err(e @ ProtocolError::UnsupportedVersion { id: Some(id), .. }) => (
+                id,
+                Response::Error {
+                    message: e.to_string(),
+                },
+            ),
+            Err(_) => continue,
+        };
+        if connection
+            .send(&Message::Response { id, response })
+            .is_err()
+        {
+            break;
+        }
+    }
+}
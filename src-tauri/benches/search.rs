@@ -0,0 +1,119 @@
+//! Build time and query latency of the catalog search index.
+//!
+//! Run with `cargo bench --bench search`. Uses a generated catalog about the
+//! size of Discord's `detectable.json`, so results don't depend on the network.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use tauri_app_lib::catalog::Game;
+use tauri_app_lib::search::{self, SearchIndex};
+
+const GAMES: usize = 22_000;
+const BUILD_RUNS: usize = 10;
+const QUERY_RUNS: usize = 200;
+
+const WORDS: &str = "\
+    age arena battle blade city craft dark dead dragon dungeon empire fantasy farm \
+    force galaxy hero hunter island kingdom knight legend light mine monster night \
+    ocean online planet quest racing realm rise shadow simulator soul space star \
+    storm survival tactics tower war witch world zombie pokémon";
+
+const QUERIES: &[&str] = &[
+    "",
+    "m",
+    "mine",
+    "dragon quest",
+    "shadw",
+    "dqs",
+    "pokemon star",
+    "nothing matches this",
+];
+
+/// Same sequence on every run, so results are comparable between runs.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, below: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % below as u64) as usize
+    }
+}
+
+fn title(rng: &mut Rng, words: &[&str]) -> String {
+    let count = 1 + rng.next(4);
+    let mut title: Vec<String> = (0..count)
+        .map(|_| {
+            let word = words[rng.next(words.len())];
+            let mut chars = word.chars();
+            let first = chars.next().unwrap().to_uppercase();
+            first.chain(chars).collect()
+        })
+        .collect();
+    if rng.next(4) == 0 {
+        title.push((2 + rng.next(7)).to_string());
+    }
+    title.join(" ")
+}
+
+fn catalog() -> Vec<Game> {
+    let words: Vec<&str> = WORDS.split_whitespace().collect();
+    let mut rng = Rng(0x5eed);
+    (0..GAMES)
+        .map(|i| {
+            let name = title(&mut rng, &words);
+            let aliases = (0..rng.next(3)).map(|_| title(&mut rng, &words)).collect();
+            Game {
+                id: (356_875_570_916_753_438u64 + i as u64).to_string(),
+                name,
+                executables: Vec::new(),
+                aliases,
+                themes: Vec::new(),
+            }
+        })
+        .collect()
+}
+
+fn stats(mut samples: Vec<Duration>) -> (Duration, Duration) {
+    samples.sort_unstable();
+    let p95 = samples[(samples.len() * 95 / 100).min(samples.len() - 1)];
+    (samples[samples.len() / 2], p95)
+}
+
+fn main() {
+    let games = catalog();
+    let aliases: usize = games.iter().map(|game| game.aliases.len()).sum();
+    println!("{} games, {} aliases", games.len(), aliases);
+
+    let builds = (0..BUILD_RUNS)
+        .map(|_| {
+            let games = games.clone();
+            let start = Instant::now();
+            black_box(SearchIndex::build(games));
+            start.elapsed()
+        })
+        .collect();
+    let (median, p95) = stats(builds);
+    println!("build                        median {median:>10.2?}  p95 {p95:>10.2?}");
+
+    let index = SearchIndex::build(games);
+    for query in QUERIES {
+        let mut total = 0;
+        let runs = (0..QUERY_RUNS)
+            .map(|_| {
+                let start = Instant::now();
+                let page = black_box(index.search(black_box(query), 0, search::DEFAULT_PAGE_SIZE));
+                let elapsed = start.elapsed();
+                total = page.total;
+                elapsed
+            })
+            .collect();
+        let (median, p95) = stats(runs);
+        println!(
+            "query {:<22} median {median:>10.2?}  p95 {p95:>10.2?}  {total} hits",
+            format!("{query:?}")
+        );
+    }
+}
@@ -0,0 +1,329 @@
+//! The game catalog: Discord's `detectable.json` (through the mirror) or the
+//! bundled `gamelist.json`. Mirrored by `Game` and `GameExecutable` in `types.d.ts`.
+
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Platform an executable is detected on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Os {
+    Win32,
+    Linux,
+    Darwin,
+    /// Anything else Discord may add; kept as written.
+    Other(String),
+}
+
+impl Os {
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "win32" => Os::Win32,
+            "linux" => Os::Linux,
+            "darwin" => Os::Darwin,
+            other => Os::Other(other.to_string()),
+        }
+    }
+
+    pub fn code(&self) -> &str {
+        match self {
+            Os::Win32 => "win32",
+            Os::Linux => "linux",
+            Os::Darwin => "darwin",
+            Os::Other(code) => code,
+        }
+    }
+}
+
+impl Serialize for Os {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Executable {
+    /// File name, or a `/`-separated path ending in one, as Discord matches it.
+    pub name: String,
+    pub os: Os,
+    pub is_launcher: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Game {
+    /// Discord application id.
+    pub id: String,
+    pub name: String,
+    pub executables: Vec<Executable>,
+    pub aliases: Vec<String>,
+    pub themes: Vec<String>,
+}
+
+/// What was wrong with one catalog entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IssueKind {
+    /// The entry is not a JSON object; it was skipped.
+    NotAnObject,
+    /// A required field is missing or empty; the game was skipped.
+    MissingField { field: &'static str },
+    /// A field has the wrong type; optional fields are left empty.
+    InvalidField { field: &'static str },
+    /// An executable was malformed and left out; the rest of the game was kept.
+    InvalidExecutable { executable: usize },
+    /// Another entry already used this id; this one was skipped.
+    DuplicateId,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CatalogIssue {
+    /// Position of the entry in the catalog array.
+    pub index: usize,
+    /// The entry's id, when it had a usable one.
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub kind: IssueKind,
+}
+
+/// A parsed catalog plus everything that had to be skipped to get it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Catalog {
+    pub games: Vec<Game>,
+    pub issues: Vec<CatalogIssue>,
+}
+
+/// Parses a catalog document. Only a document that is not a JSON array fails;
+/// malformed entries are reported in [`Catalog::issues`] and the rest is kept.
+pub fn parse(data: &[u8]) -> serde_json::Result<Catalog> {
+    let entries: Vec<Value> = serde_json::from_slice(data)?;
+    let mut catalog = Catalog::default();
+    let mut seen = HashSet::new();
+
+    for (index, entry) in entries.into_iter().enumerate() {
+        let mut issue = |id: Option<&str>, kind| {
+            catalog.issues.push(CatalogIssue {
+                index,
+                id: id.map(str::to_string),
+                kind,
+            })
+        };
+
+        let Value::Object(entry) = entry else {
+            issue(None, IssueKind::NotAnObject);
+            continue;
+        };
+        let Some(id) = id_field(&entry) else {
+            issue(None, IssueKind::MissingField { field: "id" });
+            continue;
+        };
+        let Some(name) = string_field(&entry, "name") else {
+            issue(Some(&id), IssueKind::MissingField { field: "name" });
+            continue;
+        };
+        if !seen.insert(id.clone()) {
+            issue(Some(&id), IssueKind::DuplicateId);
+            continue;
+        }
+
+        let executables = match entry.get("executables") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .enumerate()
+                .filter_map(|(position, item)| {
+                    let executable = parse_executable(item);
+                    if executable.is_none() {
+                        issue(
+                            Some(&id),
+                            IssueKind::InvalidExecutable {
+                                executable: position,
+                            },
+                        );
+                    }
+                    executable
+                })
+                .collect(),
+            Some(_) => {
+                issue(
+                    Some(&id),
+                    IssueKind::InvalidField {
+                        field: "executables",
+                    },
+                );
+                Vec::new()
+            }
+        };
+
+        let mut strings = |field: &'static str| match entry.get(field) {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Array(items)) => {
+                let strings: Vec<String> = items
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect();
+                // The strings are kept; one issue covers every item that was not
+                if strings.len() != items.len() {
+                    issue(Some(&id), IssueKind::InvalidField { field });
+                }
+                strings
+            }
+            Some(_) => {
+                issue(Some(&id), IssueKind::InvalidField { field });
+                Vec::new()
+            }
+        };
+        let aliases = strings("aliases");
+        let themes = strings("themes");
+
+        catalog.games.push(Game {
+            id,
+            name,
+            executables,
+            aliases,
+            themes,
+        });
+    }
+
+    Ok(catalog)
+}
+
+/// Snowflakes are strings in Discord's data, but a bare number is accepted too.
+fn id_field(entry: &Map<String, Value>) -> Option<String> {
+    match entry.get("id")? {
+        Value::String(id) if !id.trim().is_empty() => Some(id.trim().to_string()),
+        Value::Number(id) if id.is_u64() => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn string_field(entry: &Map<String, Value>, field: &str) -> Option<String> {
+    let value = entry.get(field)?.as_str()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn parse_executable(item: &Value) -> Option<Executable> {
+    let item = item.as_object()?;
+    Some(Executable {
+        name: string_field(item, "name")?,
+        os: Os::from_code(&string_field(item, "os")?),
+        is_launcher: item
+            .get("is_launcher")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DETECTABLE_SAMPLE: &str = include_str!("../fixtures/catalog/detectable_sample.json");
+    const MALFORMED: &str = include_str!("../fixtures/catalog/malformed.json");
+    const NOT_AN_ARRAY: &str = include_str!("../fixtures/catalog/not_an_array.json");
+    const BUNDLED: &str = include_str!("../../src/assets/gamelist.json");
+
+    fn issue(index: usize, id: Option<&str>, kind: IssueKind) -> CatalogIssue {
+        CatalogIssue {
+            index,
+            id: id.map(str::to_string),
+            kind,
+        }
+    }
+
+    #[test]
+    fn detectable_sample_parses_without_issues() {
+        let catalog = parse(DETECTABLE_SAMPLE.as_bytes()).unwrap();
+        assert!(catalog.issues.is_empty(), "{:?}", catalog.issues);
+        assert_eq!(catalog.games.len(), 3);
+
+        let game = &catalog.games[0];
+        assert_eq!(game.id, "356875570916753438");
+        assert_eq!(game.name, "Minecraft");
+        assert_eq!(game.aliases, ["Minecraft Java Edition"]);
+        assert_eq!(
+            game.executables[0],
+            Executable {
+                name: "minecraft.exe".to_string(),
+                os: Os::Win32,
+                is_launcher: true,
+            }
+        );
+        assert_eq!(game.executables[2].os, Os::Darwin);
+
+        // Games without executables, aliases or themes are kept with empty lists
+        assert!(catalog.games[2].executables.is_empty());
+        assert!(catalog.games[2].themes.is_empty());
+    }
+
+    #[test]
+    fn malformed_entries_are_reported_and_skipped() {
+        let catalog = parse(MALFORMED.as_bytes()).unwrap();
+
+        let ids: Vec<&str> = catalog.games.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(ids, ["100", "200", "300", "400"]);
+        assert_eq!(
+            catalog.issues,
+            [
+                issue(1, None, IssueKind::NotAnObject),
+                issue(2, None, IssueKind::MissingField { field: "id" }),
+                issue(3, Some("101"), IssueKind::MissingField { field: "name" }),
+                issue(
+                    4,
+                    Some("200"),
+                    IssueKind::InvalidExecutable { executable: 1 }
+                ),
+                issue(
+                    4,
+                    Some("200"),
+                    IssueKind::InvalidExecutable { executable: 2 }
+                ),
+                issue(
+                    5,
+                    Some("300"),
+                    IssueKind::InvalidField {
+                        field: "executables"
+                    }
+                ),
+                issue(5, Some("300"), IssueKind::InvalidField { field: "aliases" }),
+                issue(5, Some("300"), IssueKind::InvalidField { field: "themes" }),
+                issue(6, Some("100"), IssueKind::DuplicateId),
+            ]
+        );
+
+        // The good executable of a partly broken game survives, as do its good themes
+        assert_eq!(catalog.games[1].executables.len(), 1);
+        assert_eq!(catalog.games[2].themes, ["Action"]);
+        // Numeric ids and unknown platforms are accepted
+        assert_eq!(
+            catalog.games[3].executables[0].os,
+            Os::Other("ios".to_string())
+        );
+    }
+
+    #[test]
+    fn documents_that_are_not_an_array_fail() {
+        assert!(parse(NOT_AN_ARRAY.as_bytes()).is_err());
+        assert!(parse(b"").is_err());
+    }
+
+    #[test]
+    fn bundled_list_parses() {
+        let catalog = parse(BUNDLED.as_bytes()).unwrap();
+        assert!(catalog.issues.is_empty());
+    }
+
+    #[test]
+    fn serializes_like_the_frontend_types() {
+        let catalog = parse(DETECTABLE_SAMPLE.as_bytes()).unwrap();
+        let json = serde_json::to_value(&catalog.games[0]).unwrap();
+        assert_eq!(json["executables"][0]["os"], "win32");
+        assert_eq!(json["executables"][0]["is_launcher"], true);
+
+        let json = serde_json::to_value(issue(4, Some("200"), IssueKind::DuplicateId)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"index": 4, "id": "200", "kind": "duplicate_id"})
+        );
+    }
+}
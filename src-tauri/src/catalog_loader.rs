@@ -0,0 +1,712 @@
+use crate::catalog::{self, Catalog, CatalogIssue, Game};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri_plugin_http::reqwest::blocking::Client;
+use tauri_plugin_http::reqwest::header::{ETAG, IF_NONE_MATCH};
+use tauri_plugin_http::reqwest::StatusCode;
+
+/// Discord's `detectable.json` through the mirror, primary first.
+pub const CATALOG_URLS: [&str; 2] = [
+    "https://4ismael1.github.io/discord-detectable-mirror/detectable.json",
+    "https://cdn.jsdelivr.net/gh/4ismael1/discord-detectable-mirror@main/docs/detectable.json",
+];
+
+/// The mirror's `meta.json`, which publishes the catalog's sha256.
+pub const META_URLS: [&str; 2] = [
+    "https://4ismael1.github.io/discord-detectable-mirror/meta.json",
+    "https://cdn.jsdelivr.net/gh/4ismael1/discord-detectable-mirror@main/docs/meta.json",
+];
+
+const CACHE_FILE: &str = "catalog.json";
+const CACHE_INFO_FILE: &str = "catalog.info.json";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// `meta.json` as published by the mirror. Mirrored by `MirrorMeta` in the frontend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorMeta {
+    #[serde(default)]
+    pub last_updated: String,
+    /// ETag of Discord's own endpoint, as seen by the mirror.
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub source_url: String,
+    pub sha256: String,
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub items_count: Option<u64>,
+}
+
+/// Stored next to the cached catalog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheInfo {
+    sha256: String,
+    /// ETag of the response the copy came from, for `If-None-Match`.
+    etag: Option<String>,
+    source_url: String,
+    fetched_at: u64,
+}
+
+/// Where the returned catalog came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogOrigin {
+    /// Downloaded now and stored as the new cached copy.
+    Downloaded,
+    /// The cached copy, confirmed current by the published hash or a `304`.
+    Cache,
+    /// The cached copy, used because nothing newer could be downloaded and verified.
+    StaleCache,
+}
+
+/// Something that went wrong with one source while loading. Not fatal on its own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SourceProblem {
+    Unreachable {
+        url: String,
+        error: String,
+    },
+    HttpStatus {
+        url: String,
+        status: u16,
+    },
+    HashMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+    /// Not a catalog document at all.
+    Invalid {
+        url: String,
+        error: String,
+    },
+    /// No `meta.json` was reachable, so a download could not be verified.
+    MetaUnavailable,
+}
+
+impl fmt::Display for SourceProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceProblem::Unreachable { url, error } => write!(f, "{url}: {error}"),
+            SourceProblem::HttpStatus { url, status } => write!(f, "{url}: HTTP {status}"),
+            SourceProblem::HashMismatch {
+                url,
+                expected,
+                actual,
+            } => write!(f, "{url}: sha256 {actual}, expected {expected}"),
+            SourceProblem::Invalid { url, error } => write!(f, "{url}: {error}"),
+            SourceProblem::MetaUnavailable => f.write_str("meta.json unavailable"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadedCatalog {
+    pub catalog: Catalog,
+    pub origin: CatalogOrigin,
+    pub sha256: String,
+    /// The content matches the sha256 published in `meta.json`.
+    pub verified: bool,
+    /// Unix time in seconds when this content was downloaded.
+    pub fetched_at: u64,
+    pub meta: Option<MirrorMeta>,
+    pub problems: Vec<SourceProblem>,
+}
+
+impl LoadedCatalog {
+    /// Takes the games out, e.g. for the search index, and summarizes the rest.
+    pub fn into_games(self) -> (Vec<Game>, CatalogSummary) {
+        let summary = CatalogSummary {
+            game_count: self.catalog.games.len(),
+            issues: self.catalog.issues,
+            origin: self.origin,
+            sha256: self.sha256,
+            verified: self.verified,
+            fetched_at: self.fetched_at,
+            meta: self.meta,
+            problems: self.problems,
+        };
+        (self.catalog.games, summary)
+    }
+}
+
+/// What the frontend learns about a loaded catalog. The games stay in the
+/// backend and are reached through `search_catalog`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogSummary {
+    pub game_count: usize,
+    pub issues: Vec<CatalogIssue>,
+    pub origin: CatalogOrigin,
+    pub sha256: String,
+    pub verified: bool,
+    pub fetched_at: u64,
+    pub meta: Option<MirrorMeta>,
+    pub problems: Vec<SourceProblem>,
+}
+
+/// A `200` response body and its ETag.
+struct Fetched {
+    data: Vec<u8>,
+    etag: Option<String>,
+}
+
+/// The last copy that passed verification, as read back from disk.
+struct Cached {
+    info: CacheInfo,
+    catalog: Catalog,
+}
+
+/// Downloads the catalog, checks it against the mirror's published sha256 and
+/// keeps the last good copy on disk.
+///
+/// An unchanged hash skips the download; otherwise the copy's ETag makes the
+/// request conditional. Without network, or when no download can be verified,
+/// the cached copy is returned as [`CatalogOrigin::StaleCache`].
+pub struct CatalogLoader {
+    client: Client,
+    catalog_urls: Vec<String>,
+    meta_urls: Vec<String>,
+    cache_dir: PathBuf,
+}
+
+impl CatalogLoader {
+    pub fn new(cache_dir: PathBuf) -> io::Result<Self> {
+        Self::with_urls(
+            cache_dir,
+            CATALOG_URLS.iter().map(|url| url.to_string()).collect(),
+            META_URLS.iter().map(|url| url.to_string()).collect(),
+        )
+    }
+
+    pub fn with_urls(
+        cache_dir: PathBuf,
+        catalog_urls: Vec<String>,
+        meta_urls: Vec<String>,
+    ) -> io::Result<Self> {
+        let client = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(io::Error::other)?;
+        Ok(CatalogLoader {
+            client,
+            catalog_urls,
+            meta_urls,
+            cache_dir,
+        })
+    }
+
+    /// Blocks on the network; call it off the async runtime.
+    pub fn load(&self, now: u64) -> io::Result<LoadedCatalog> {
+        let mut problems = Vec::new();
+        let meta = self.fetch_meta();
+        if meta.is_none() {
+            problems.push(SourceProblem::MetaUnavailable);
+        }
+        let cached = self.read_cache();
+
+        if let (Some(published), Some(cached)) = (&meta, &cached) {
+            if same_hash(&published.sha256, &cached.info.sha256) {
+                return Ok(from_cache(
+                    cached,
+                    CatalogOrigin::Cache,
+                    true,
+                    &meta,
+                    problems,
+                ));
+            }
+        }
+
+        for url in &self.catalog_urls {
+            let etag = cached
+                .as_ref()
+                .and_then(|cached| cached.info.etag.as_deref());
+            let Fetched { data, etag } = match self.get(url, etag) {
+                Ok(Some(response)) => response,
+                Ok(None) => {
+                    // 304: the server has nothing newer than our copy. With a
+                    // published hash that differs, this source is just behind.
+                    let Some(cached) = &cached else { continue };
+                    if meta.is_some() {
+                        continue;
+                    }
+                    return Ok(from_cache(
+                        cached,
+                        CatalogOrigin::Cache,
+                        false,
+                        &meta,
+                        problems,
+                    ));
+                }
+                Err(problem) => {
+                    problems.push(problem);
+                    continue;
+                }
+            };
+
+            let sha256 = sha256_hex(&data);
+            match &meta {
+                Some(meta) if !same_hash(&meta.sha256, &sha256) => {
+                    problems.push(SourceProblem::HashMismatch {
+                        url: url.clone(),
+                        expected: meta.sha256.to_lowercase(),
+                        actual: sha256,
+                    });
+                    continue;
+                }
+                // Never replace a verified copy with one that cannot be checked
+                None if cached.is_some() => break,
+                _ => {}
+            }
+
+            let catalog = match catalog::parse(&data) {
+                Ok(catalog) => catalog,
+                Err(e) => {
+                    problems.push(SourceProblem::Invalid {
+                        url: url.clone(),
+                        error: e.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let info = CacheInfo {
+                sha256: sha256.clone(),
+                etag,
+                source_url: url.clone(),
+                fetched_at: now,
+            };
+            // A failed write only costs the next start a download
+            let _ = self.write_cache(&data, &info);
+            return Ok(LoadedCatalog {
+                catalog,
+                origin: CatalogOrigin::Downloaded,
+                sha256,
+                verified: meta.is_some(),
+                fetched_at: now,
+                meta,
+                problems,
+            });
+        }
+
+        match &cached {
+            Some(cached) => Ok(from_cache(
+                cached,
+                CatalogOrigin::StaleCache,
+                false,
+                &meta,
+                problems,
+            )),
+            None => {
+                let detail = problems
+                    .iter()
+                    .map(SourceProblem::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                Err(io::Error::other(detail))
+            }
+        }
+    }
+
+    fn fetch_meta(&self) -> Option<MirrorMeta> {
+        self.meta_urls.iter().find_map(|url| {
+            let fetched = self.get(url, None).ok()??;
+            serde_json::from_slice(&fetched.data).ok()
+        })
+    }
+
+    /// `Ok(None)` on `304 Not Modified`.
+    fn get(
+        &self,
+        url: &str,
+        if_none_match: Option<&str>,
+    ) -> Result<Option<Fetched>, SourceProblem> {
+        let unreachable = |e: tauri_plugin_http::reqwest::Error| SourceProblem::Unreachable {
+            url: url.to_string(),
+            error: e.to_string(),
+        };
+
+        let mut request = self.client.get(url);
+        if let Some(etag) = if_none_match {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().map_err(unreachable)?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(SourceProblem::HttpStatus {
+                url: url.to_string(),
+                status: status.as_u16(),
+            });
+        }
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let data = response.bytes().map_err(unreachable)?;
+        Ok(Some(Fetched {
+            data: data.to_vec(),
+            etag,
+        }))
+    }
+
+    /// The cached copy, if it is still intact.
+    fn read_cache(&self) -> Option<Cached> {
+        let info: CacheInfo =
+            serde_json::from_slice(&std::fs::read(self.cache_dir.join(CACHE_INFO_FILE)).ok()?)
+                .ok()?;
+        let data = std::fs::read(self.cache_dir.join(CACHE_FILE)).ok()?;
+        if !same_hash(&sha256_hex(&data), &info.sha256) {
+            return None;
+        }
+        let catalog = catalog::parse(&data).ok()?;
+        Some(Cached { info, catalog })
+    }
+
+    /// The data goes first: if the info write fails, the hashes no longer match
+    /// and the copy is ignored instead of trusted.
+    fn write_cache(&self, data: &[u8], info: &CacheInfo) -> io::Result<()> {
+        std::fs::create_dir_all(&self.cache_dir)?;
+        write_atomic(&self.cache_dir.join(CACHE_FILE), data)?;
+        let info = serde_json::to_vec_pretty(info).map_err(io::Error::other)?;
+        write_atomic(&self.cache_dir.join(CACHE_INFO_FILE), &info)
+    }
+}
+
+fn from_cache(
+    cached: &Cached,
+    origin: CatalogOrigin,
+    verified: bool,
+    meta: &Option<MirrorMeta>,
+    problems: Vec<SourceProblem>,
+) -> LoadedCatalog {
+    LoadedCatalog {
+        catalog: cached.catalog.clone(),
+        origin,
+        sha256: cached.info.sha256.clone(),
+        verified,
+        fetched_at: cached.info.fetched_at,
+        meta: meta.clone(),
+        problems,
+    }
+}
+
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn same_hash(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    const CATALOG: &str = include_str!("../fixtures/catalog/detectable_sample.json");
+    // Nothing listens on port 1, so connections are refused right away
+    const OFFLINE: &str = "http://127.0.0.1:1";
+
+    struct Route {
+        status: u16,
+        body: Vec<u8>,
+        etag: Option<String>,
+    }
+
+    /// Minimal HTTP/1.1 server standing in for the mirror.
+    #[derive(Clone)]
+    struct StandIn {
+        base: String,
+        routes: Arc<Mutex<HashMap<String, Route>>>,
+        /// `path` or `path If-None-Match: etag` for every request served.
+        requests: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StandIn {
+        fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let server = StandIn {
+                base: format!("http://{}", listener.local_addr().unwrap()),
+                routes: Arc::default(),
+                requests: Arc::default(),
+            };
+            let handler = server.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    handler.serve(stream);
+                }
+            });
+            server
+        }
+
+        fn serve(&self, mut stream: std::net::TcpStream) {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let path = request_line.split(' ').nth(1).unwrap_or("/").to_string();
+            let mut if_none_match = None;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("if-none-match") {
+                        if_none_match = Some(value.trim().to_string());
+                    }
+                }
+            }
+
+            let mut log = path.clone();
+            if let Some(etag) = &if_none_match {
+                log.push_str(&format!(" If-None-Match: {etag}"));
+            }
+            self.requests.lock().unwrap().push(log);
+
+            let routes = self.routes.lock().unwrap();
+            let (status, body, etag) = match routes.get(&path) {
+                Some(route) if route.etag.is_some() && route.etag == if_none_match => {
+                    (304, Vec::new(), route.etag.clone())
+                }
+                Some(route) => (route.status, route.body.clone(), route.etag.clone()),
+                None => (404, Vec::new(), None),
+            };
+            let mut head = format!(
+                "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n",
+                body.len()
+            );
+            if let Some(etag) = etag {
+                head.push_str(&format!("ETag: {etag}\r\n"));
+            }
+            head.push_str("\r\n");
+            let _ = stream.write_all(head.as_bytes());
+            let _ = stream.write_all(&body);
+        }
+
+        fn route(&self, path: &str, status: u16, body: &[u8], etag: Option<&str>) {
+            self.routes.lock().unwrap().insert(
+                path.to_string(),
+                Route {
+                    status,
+                    body: body.to_vec(),
+                    etag: etag.map(str::to_string),
+                },
+            );
+        }
+
+        fn publish(&self, catalog: &[u8], etag: &str) {
+            self.route("/detectable.json", 200, catalog, Some(etag));
+            let meta = serde_json::json!({
+                "last_updated": "2026-10-01T00:00:00Z",
+                "etag": null,
+                "source_url": "https://discord.com/api/applications/detectable",
+                "sha256": sha256_hex(catalog).to_uppercase(),
+                "status": "ok",
+            });
+            self.route("/meta.json", 200, meta.to_string().as_bytes(), None);
+        }
+
+        fn url(&self, path: &str) -> String {
+            format!("{}{path}", self.base)
+        }
+
+        fn take_requests(&self) -> Vec<String> {
+            std::mem::take(&mut *self.requests.lock().unwrap())
+        }
+    }
+
+    fn loader(dir: &Path, catalog_urls: &[String], meta_urls: &[String]) -> CatalogLoader {
+        CatalogLoader::with_urls(dir.to_path_buf(), catalog_urls.to_vec(), meta_urls.to_vec())
+            .unwrap()
+    }
+
+    fn mirror_loader(dir: &Path, mirror: &StandIn) -> CatalogLoader {
+        loader(
+            dir,
+            &[mirror.url("/detectable.json")],
+            &[mirror.url("/meta.json")],
+        )
+    }
+
+    #[test]
+    fn downloads_verifies_and_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+
+        let loaded = mirror_loader(dir.path(), &mirror).load(100).unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::Downloaded);
+        assert!(loaded.verified);
+        assert_eq!(loaded.catalog.games.len(), 3);
+        assert_eq!(loaded.fetched_at, 100);
+        assert!(loaded.problems.is_empty());
+        assert_eq!(
+            std::fs::read(dir.path().join(CACHE_FILE)).unwrap(),
+            CATALOG.as_bytes()
+        );
+    }
+
+    #[test]
+    fn unchanged_hash_skips_the_download() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+        mirror_loader(dir.path(), &mirror).load(100).unwrap();
+        mirror.take_requests();
+
+        let loaded = mirror_loader(dir.path(), &mirror).load(200).unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::Cache);
+        assert!(loaded.verified);
+        assert_eq!(loaded.fetched_at, 100);
+        assert_eq!(mirror.take_requests(), ["/meta.json"]);
+    }
+
+    #[test]
+    fn etag_makes_the_refresh_conditional() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+        mirror_loader(dir.path(), &mirror).load(100).unwrap();
+
+        // Without meta.json the hash can't be compared, but the ETag still works
+        mirror.route("/meta.json", 503, b"", None);
+        mirror.take_requests();
+        let loaded = mirror_loader(dir.path(), &mirror).load(200).unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::Cache);
+        assert!(!loaded.verified);
+        assert_eq!(
+            mirror.take_requests(),
+            ["/meta.json", "/detectable.json If-None-Match: \"v1\""]
+        );
+    }
+
+    #[test]
+    fn new_versions_replace_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+        mirror_loader(dir.path(), &mirror).load(100).unwrap();
+
+        let updated = br#"[{"id": "1", "name": "New", "executables": []}]"#;
+        mirror.publish(updated, "\"v2\"");
+        let loaded = mirror_loader(dir.path(), &mirror).load(200).unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::Downloaded);
+        assert_eq!(loaded.catalog.games[0].name, "New");
+        assert_eq!(std::fs::read(dir.path().join(CACHE_FILE)).unwrap(), updated);
+    }
+
+    #[test]
+    fn sources_behind_the_published_hash_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+        mirror_loader(dir.path(), &mirror).load(100).unwrap();
+
+        // meta.json moved on but the catalog server still has the old version
+        let updated = br#"[{"id": "1", "name": "New"}]"#;
+        mirror.publish(updated, "\"v2\"");
+        mirror.route("/detectable.json", 200, CATALOG.as_bytes(), Some("\"v1\""));
+        let loaded = mirror_loader(dir.path(), &mirror).load(200).unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::StaleCache);
+        assert_eq!(loaded.catalog.games.len(), 3);
+    }
+
+    #[test]
+    fn mismatched_downloads_fall_through_to_the_next_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+        mirror.route("/tampered.json", 200, b"[]", None);
+
+        let loaded = loader(
+            dir.path(),
+            &[mirror.url("/tampered.json"), mirror.url("/detectable.json")],
+            &[mirror.url("/meta.json")],
+        )
+        .load(100)
+        .unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::Downloaded);
+        assert!(matches!(
+            loaded.problems.as_slice(),
+            [SourceProblem::HashMismatch { url, .. }] if url.ends_with("/tampered.json")
+        ));
+    }
+
+    #[test]
+    fn offline_uses_the_cache_or_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let offline = |dir: &Path| {
+            loader(
+                dir,
+                &[format!("{OFFLINE}/detectable.json")],
+                &[format!("{OFFLINE}/meta.json")],
+            )
+        };
+        assert!(offline(dir.path()).load(100).is_err());
+
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+        mirror_loader(dir.path(), &mirror).load(100).unwrap();
+
+        let loaded = offline(dir.path()).load(200).unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::StaleCache);
+        assert_eq!(loaded.catalog.games.len(), 3);
+        assert!(loaded
+            .problems
+            .iter()
+            .any(|problem| matches!(problem, SourceProblem::Unreachable { .. })));
+    }
+
+    #[test]
+    fn unverifiable_downloads_never_replace_a_good_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+        mirror_loader(dir.path(), &mirror).load(100).unwrap();
+
+        mirror.route("/meta.json", 404, b"", None);
+        mirror.route("/detectable.json", 200, b"[]", Some("\"v2\""));
+        let loaded = mirror_loader(dir.path(), &mirror).load(200).unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::StaleCache);
+        assert_eq!(loaded.catalog.games.len(), 3);
+    }
+
+    #[test]
+    fn corrupted_cache_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = StandIn::start();
+        mirror.publish(CATALOG.as_bytes(), "\"v1\"");
+        mirror_loader(dir.path(), &mirror).load(100).unwrap();
+        std::fs::write(dir.path().join(CACHE_FILE), b"[]").unwrap();
+
+        mirror.take_requests();
+        let loaded = mirror_loader(dir.path(), &mirror).load(200).unwrap();
+        assert_eq!(loaded.origin, CatalogOrigin::Downloaded);
+        // No If-None-Match: the damaged copy's ETag is not trusted either
+        assert_eq!(mirror.take_requests(), ["/meta.json", "/detectable.json"]);
+    }
+}
@@ -0,0 +1,246 @@
+use discord_quest_core::game_path::STORE_DIR;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployOutcome {
+    /// The target already had the current stub; nothing was written.
+    Unchanged,
+    Created,
+    /// An older stub was replaced.
+    Updated,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Deployment {
+    pub outcome: DeployOutcome,
+    /// Whether this call wrote a hard link to the stored copy (false for copies and unchanged targets).
+    pub linked: bool,
+}
+
+/// Places the bundled stub at game paths without duplicating identical bytes.
+///
+/// The bundled executable is hashed once and stored as `<root>/.stubs/<sha256>.exe`.
+/// Targets are hard links to that file where the filesystem allows it, and a
+/// full copy otherwise. Existing targets are only rewritten if their hash
+/// differs, always through a temp file and a rename so a half-written stub
+/// never takes the place of a working one.
+pub struct StubDeployer {
+    stored: PathBuf,
+    digest: String,
+}
+
+impl StubDeployer {
+    pub fn new(source: &Path, games_root: &Path) -> io::Result<Self> {
+        let digest = hash_file(source)?;
+        let store = games_root.join(STORE_DIR);
+        std::fs::create_dir_all(&store)?;
+
+        let stored = store.join(format!("{}.exe", digest));
+        if hash_file(&stored).ok().as_deref() != Some(digest.as_str()) {
+            replace_atomically(&stored, |tmp| std::fs::copy(source, tmp).map(|_| ()))?;
+        }
+        remove_old_versions(&store, &stored);
+
+        Ok(StubDeployer { stored, digest })
+    }
+
+    pub fn digest(&self) -> &str {
+        &self.digest
+    }
+
+    pub fn deploy(&self, target: &Path) -> io::Result<Deployment> {
+        let existing = match hash_file(target) {
+            Ok(digest) => Some(digest),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        if existing.as_deref() == Some(self.digest.as_str()) {
+            return Ok(Deployment {
+                outcome: DeployOutcome::Unchanged,
+                linked: false,
+            });
+        }
+
+        let mut linked = false;
+        replace_atomically(target, |tmp| match std::fs::hard_link(&self.stored, tmp) {
+            Ok(()) => {
+                linked = true;
+                Ok(())
+            }
+            // Different volume, FAT32, ... fall back to a plain copy
+            Err(_) => std::fs::copy(&self.stored, tmp).map(|_| ()),
+        })?;
+
+        Ok(Deployment {
+            outcome: if existing.is_some() {
+                DeployOutcome::Updated
+            } else {
+                DeployOutcome::Created
+            },
+            linked,
+        })
+    }
+}
+
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Writes a sibling temp file with `write` and renames it over `target`.
+fn replace_atomically(
+    target: &Path,
+    write: impl FnOnce(&Path) -> io::Result<()>,
+) -> io::Result<()> {
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp = target.with_file_name(tmp_name);
+
+    let _ = std::fs::remove_file(&tmp);
+    let result = write(&tmp).and_then(|()| std::fs::rename(&tmp, target));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Drops stored copies of previous stub versions. Games still linked to them keep
+/// their data until they are redeployed.
+fn remove_old_versions(store: &Path, current: &Path) {
+    let Ok(items) = std::fs::read_dir(store) else {
+        return;
+    };
+    for item in items.flatten() {
+        let path = item.path();
+        if path != current {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixture {
+        _dir: tempfile::TempDir,
+        source: PathBuf,
+        root: PathBuf,
+    }
+
+    fn fixture(contents: &[u8]) -> Fixture {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src-win.exe");
+        std::fs::write(&source, contents).unwrap();
+        let root = dir.path().join("games");
+        Fixture {
+            _dir: dir,
+            source,
+            root,
+        }
+    }
+
+    fn target(root: &Path) -> PathBuf {
+        let folder = root.join("123").join("bin");
+        std::fs::create_dir_all(&folder).unwrap();
+        folder.join("game.exe")
+    }
+
+    #[test]
+    fn hashes_known_content() {
+        let fixture = fixture(b"abc");
+        assert_eq!(
+            hash_file(&fixture.source).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn creates_then_skips_identical_copy() {
+        let fixture = fixture(b"stub v1");
+        let deployer = StubDeployer::new(&fixture.source, &fixture.root).unwrap();
+        let target = target(&fixture.root);
+
+        let first = deployer.deploy(&target).unwrap();
+        assert_eq!(first.outcome, DeployOutcome::Created);
+        assert_eq!(std::fs::read(&target).unwrap(), b"stub v1");
+
+        let second = deployer.deploy(&target).unwrap();
+        assert_eq!(second.outcome, DeployOutcome::Unchanged);
+    }
+
+    #[test]
+    fn replaces_outdated_copy() {
+        let fixture = fixture(b"stub v2");
+        let target = target(&fixture.root);
+        std::fs::write(&target, b"stub v1").unwrap();
+
+        let deployer = StubDeployer::new(&fixture.source, &fixture.root).unwrap();
+        let deployment = deployer.deploy(&target).unwrap();
+
+        assert_eq!(deployment.outcome, DeployOutcome::Updated);
+        assert_eq!(std::fs::read(&target).unwrap(), b"stub v2");
+        let leftovers: Vec<_> = std::fs::read_dir(target.parent().unwrap())
+            .unwrap()
+            .map(|item| item.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers, vec!["game.exe"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn targets_share_the_stored_copy() {
+        use std::os::unix::fs::MetadataExt;
+
+        let fixture = fixture(b"stub");
+        let deployer = StubDeployer::new(&fixture.source, &fixture.root).unwrap();
+        let first = target(&fixture.root);
+        let second = fixture.root.join("456").join("other.exe");
+        std::fs::create_dir_all(second.parent().unwrap()).unwrap();
+
+        assert!(deployer.deploy(&first).unwrap().linked);
+        assert!(deployer.deploy(&second).unwrap().linked);
+        let stored = fixture
+            .root
+            .join(STORE_DIR)
+            .join(format!("{}.exe", deployer.digest()));
+        assert_eq!(std::fs::metadata(&stored).unwrap().nlink(), 3);
+        assert_eq!(
+            std::fs::metadata(&first).unwrap().ino(),
+            std::fs::metadata(&second).unwrap().ino()
+        );
+    }
+
+    #[test]
+    fn new_version_drops_old_stored_copy() {
+        let fixture = fixture(b"stub v1");
+        let old = StubDeployer::new(&fixture.source, &fixture.root).unwrap();
+        std::fs::write(&fixture.source, b"stub v2").unwrap();
+        let new = StubDeployer::new(&fixture.source, &fixture.root).unwrap();
+
+        let store = fixture.root.join(STORE_DIR);
+        assert!(!store.join(format!("{}.exe", old.digest())).exists());
+        assert!(store.join(format!("{}.exe", new.digest())).exists());
+    }
+}
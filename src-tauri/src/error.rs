@@ -0,0 +1,170 @@
+use crate::settings::SettingsError;
+use discord_quest_core::game_path::GamePathError;
+use discord_quest_i18n::{self as i18n, Lang};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::io;
+use std::sync::RwLock;
+
+static MESSAGE_LANG: RwLock<Lang> = RwLock::new(Lang::Es);
+
+/// Language of error messages sent to the frontend; follows the `language` setting.
+pub fn set_message_lang(lang: Lang) {
+    *MESSAGE_LANG.write().unwrap() = lang;
+}
+
+pub fn message_lang() -> Lang {
+    *MESSAGE_LANG.read().unwrap()
+}
+
+/// Error returned by every Tauri command.
+///
+/// Serialized as `{ "code": "SpawnFailed", "message": "..." }` so the frontend
+/// can branch on `code` and only show `message` to the user.
+#[derive(Debug)]
+pub enum CommandError {
+    InvalidPath(GamePathError),
+    DirCreateFailed(io::Error),
+    ResourceMissing(String),
+    CopyFailed(io::Error),
+    SpawnFailed(io::Error),
+    /// The concurrency limit from the settings, which was reached.
+    LimitReached(u32),
+    /// A stub with this key is already running or stopping.
+    AlreadyRunning(String),
+    NotRunning(String),
+    NotOwned(String),
+    InvalidSettings(SettingsError),
+    SettingsSaveFailed(io::Error),
+    InventoryFailed(io::Error),
+    StubUnreachable(io::Error),
+    HistoryFailed(io::Error),
+    InvalidCatalog(serde_json::Error),
+    CatalogUnavailable(io::Error),
+    /// A search ran before `load_catalog` succeeded.
+    CatalogNotLoaded,
+}
+
+impl CommandError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::InvalidPath(_) => "InvalidPath",
+            CommandError::DirCreateFailed(_) => "DirCreateFailed",
+            CommandError::ResourceMissing(_) => "ResourceMissing",
+            CommandError::CopyFailed(_) => "CopyFailed",
+            CommandError::SpawnFailed(_) => "SpawnFailed",
+            CommandError::LimitReached(_) => "LimitReached",
+            CommandError::AlreadyRunning(_) => "AlreadyRunning",
+            CommandError::NotRunning(_) => "NotRunning",
+            CommandError::NotOwned(_) => "NotOwned",
+            CommandError::InvalidSettings(_) => "InvalidSettings",
+            CommandError::SettingsSaveFailed(_) => "SettingsSaveFailed",
+            CommandError::InventoryFailed(_) => "InventoryFailed",
+            CommandError::StubUnreachable(_) => "StubUnreachable",
+            CommandError::HistoryFailed(_) => "HistoryFailed",
+            CommandError::InvalidCatalog(_) => "InvalidCatalog",
+            CommandError::CatalogUnavailable(_) => "CatalogUnavailable",
+            CommandError::CatalogNotLoaded => "CatalogNotLoaded",
+        }
+    }
+
+    pub fn message(&self, lang: Lang) -> String {
+        let detail_message;
+        let (key, detail): (&'static str, &dyn fmt::Display) = match self {
+            CommandError::InvalidPath(e) => {
+                detail_message = e.message(lang);
+                ("error.invalid_path", &detail_message)
+            }
+            CommandError::DirCreateFailed(e) => ("error.dir_create_failed", e),
+            CommandError::ResourceMissing(name) => ("error.resource_missing", name),
+            CommandError::CopyFailed(e) => ("error.copy_failed", e),
+            CommandError::SpawnFailed(e) => ("error.spawn_failed", e),
+            CommandError::LimitReached(limit) => ("error.limit_reached", limit),
+            CommandError::AlreadyRunning(key) => ("error.already_running", key),
+            CommandError::NotRunning(key) => ("error.not_running", key),
+            CommandError::NotOwned(name) => ("error.not_owned", name),
+            CommandError::InvalidSettings(e) => {
+                detail_message = e.message(lang);
+                ("error.invalid_settings", &detail_message)
+            }
+            CommandError::SettingsSaveFailed(e) => ("error.settings_save_failed", e),
+            CommandError::InventoryFailed(e) => ("error.inventory_failed", e),
+            CommandError::StubUnreachable(e) => ("error.stub_unreachable", e),
+            CommandError::HistoryFailed(e) => ("error.history_failed", e),
+            CommandError::InvalidCatalog(e) => ("error.invalid_catalog", e),
+            CommandError::CatalogUnavailable(e) => ("error.catalog_unavailable", e),
+            CommandError::CatalogNotLoaded => ("error.catalog_not_loaded", &""),
+        };
+        i18n::format(lang, key, &[detail])
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(message_lang()))
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<GamePathError> for CommandError {
+    fn from(e: GamePathError) -> Self {
+        CommandError::InvalidPath(e)
+    }
+}
+
+impl From<SettingsError> for CommandError {
+    fn from(e: SettingsError) -> Self {
+        CommandError::InvalidSettings(e)
+    }
+}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommandError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    // The message language is global; tests that change it take turns
+    static LANG_LOCK: Mutex<()> = Mutex::new(());
+
+    fn serialized(error: &CommandError, lang: Lang) -> serde_json::Value {
+        let _guard = LANG_LOCK.lock().unwrap();
+        set_message_lang(lang);
+        let value = serde_json::to_value(error).unwrap();
+        set_message_lang(Lang::default());
+        value
+    }
+
+    #[test]
+    fn serializes_code_and_spanish_message() {
+        assert_eq!(
+            serialized(&CommandError::LimitReached(3), Lang::Es),
+            json!({
+                "code": "LimitReached",
+                "message": "Ya hay 3 juegos en ejecución, el máximo configurado",
+            })
+        );
+    }
+
+    #[test]
+    fn serializes_code_and_english_message() {
+        assert_eq!(
+            serialized(&CommandError::NotRunning("1:game.exe".into()), Lang::En),
+            json!({
+                "code": "NotRunning",
+                "message": "The process is not running: 1:game.exe",
+            })
+        );
+    }
+}
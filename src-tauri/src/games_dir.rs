@@ -0,0 +1,463 @@
+use crate::deploy::hash_file;
+use crate::platform::{file_identity, FileId};
+use discord_quest_core::game_path::{games_root_for, is_app_folder, usage_key, STORE_DIR};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// One fake executable found under the games root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GameEntry {
+    pub app_id: String,
+    /// Folder inside `games/<app_id>/`, `/`-separated; empty for the app folder itself.
+    pub path: String,
+    pub executable_name: String,
+    pub size_bytes: u64,
+    /// Shares its data with the stored stub or other games, so deleting it alone frees nothing.
+    pub linked: bool,
+    /// Unix time in seconds of the last launch, or of the copy if it was never launched.
+    pub last_used: u64,
+    pub ever_launched: bool,
+    #[serde(skip)]
+    pub full_path: PathBuf,
+    #[serde(skip)]
+    file_id: FileId,
+    #[serde(skip)]
+    usage_key: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GamesInventory {
+    pub entries: Vec<GameEntry>,
+    /// Disk space the entries take, counting data shared through hard links once.
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    pub removed: Vec<GameEntry>,
+    /// Left in place because the stub is running.
+    pub skipped_running: Vec<GameEntry>,
+    pub failed: Vec<CleanupFailure>,
+    /// Only counts files whose last link was removed.
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupFailure {
+    pub entry: GameEntry,
+    pub error: String,
+}
+
+/// What `prune` removes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruneRule {
+    /// Stubs that were created but never launched.
+    NeverLaunched,
+    /// Stubs whose last use is more than this many days ago.
+    OlderThanDays(u64),
+}
+
+/// The games root next to the running executable, used unless the settings name another.
+pub fn default_games_root() -> PathBuf {
+    games_root_for(&std::env::current_exe().unwrap_or_default())
+}
+
+/// Last launch time of every stub, stored as `usage.json` next to the process state.
+pub struct UsageLog {
+    path: PathBuf,
+    last_used: Mutex<HashMap<String, u64>>,
+}
+
+impl UsageLog {
+    pub fn load(path: PathBuf) -> Self {
+        let last_used = std::fs::read(&path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default();
+        UsageLog {
+            path,
+            last_used: Mutex::new(last_used),
+        }
+    }
+
+    pub fn record(&self, key: String, at: u64) -> io::Result<()> {
+        let mut last_used = self.last_used.lock().unwrap();
+        last_used.insert(key, at);
+        self.save(&last_used)
+    }
+
+    fn get(&self, key: &str) -> Option<u64> {
+        self.last_used.lock().unwrap().get(key).copied()
+    }
+
+    fn forget(&self, keys: &[String]) -> io::Result<()> {
+        let mut last_used = self.last_used.lock().unwrap();
+        for key in keys {
+            last_used.remove(key);
+        }
+        self.save(&last_used)
+    }
+
+    fn save(&self, last_used: &HashMap<String, u64>) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = serde_json::to_vec_pretty(last_used).map_err(io::Error::other)?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
+
+/// The stub copies in `<root>/.stubs/`. A file elsewhere in the games root is
+/// only treated as a stub if it is one of them, through a hard link or by
+/// content, so nothing the app did not deploy is listed or deleted.
+struct StubStore {
+    ids: HashSet<FileId>,
+    digests: HashSet<String>,
+    sizes: HashSet<u64>,
+}
+
+impl StubStore {
+    fn load(root: &Path) -> io::Result<Self> {
+        let mut store = StubStore {
+            ids: HashSet::new(),
+            digests: HashSet::new(),
+            sizes: HashSet::new(),
+        };
+        let items = match std::fs::read_dir(root.join(STORE_DIR)) {
+            Ok(items) => items,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(store),
+            Err(e) => return Err(e),
+        };
+        for item in items {
+            let path = item?.path();
+            if !path.is_file() {
+                continue;
+            }
+            store.ids.insert(file_identity(&path)?.id);
+            store.digests.insert(hash_file(&path)?);
+            store.sizes.insert(std::fs::metadata(&path)?.len());
+        }
+        Ok(store)
+    }
+
+    fn is_stub(&self, path: &Path, id: FileId, size: u64) -> bool {
+        if self.ids.contains(&id) {
+            return true;
+        }
+        // Copies made where hard links are not possible; only hash plausible ones
+        self.sizes.contains(&size)
+            && hash_file(path).is_ok_and(|digest| self.digests.contains(&digest))
+    }
+}
+
+/// Lists every deployed stub under `root`. Files that are not stubs are left
+/// out. A missing root is an empty inventory.
+pub fn inventory(root: &Path, usage: &UsageLog) -> io::Result<GamesInventory> {
+    let mut entries = Vec::new();
+    if root.is_dir() {
+        let store = StubStore::load(root)?;
+        for app_dir in std::fs::read_dir(root)? {
+            let app_dir = app_dir?;
+            let app_id = app_dir.file_name().to_string_lossy().into_owned();
+            // Skip files and our own dot folders such as the stub store
+            if !app_dir.file_type()?.is_dir() || !is_app_folder(&app_id) {
+                continue;
+            }
+            let mut walk = Walk {
+                root,
+                store: &store,
+                app_id: &app_id,
+                usage,
+                entries: &mut entries,
+            };
+            walk.collect_files(&app_dir.path(), &mut Vec::new())?;
+        }
+    }
+    entries.sort_by(|a, b| a.full_path.cmp(&b.full_path));
+    let mut counted = HashSet::new();
+    let total_bytes = entries
+        .iter()
+        .filter(|entry| counted.insert(entry.file_id))
+        .map(|entry| entry.size_bytes)
+        .sum();
+    Ok(GamesInventory {
+        entries,
+        total_bytes,
+    })
+}
+
+struct Walk<'a> {
+    root: &'a Path,
+    store: &'a StubStore,
+    app_id: &'a str,
+    usage: &'a UsageLog,
+    entries: &'a mut Vec<GameEntry>,
+}
+
+impl Walk<'_> {
+    fn collect_files(&mut self, dir: &Path, folders: &mut Vec<String>) -> io::Result<()> {
+        for item in std::fs::read_dir(dir)? {
+            let item = item?;
+            let name = item.file_name().to_string_lossy().into_owned();
+            let file_type = item.file_type()?;
+            if file_type.is_dir() {
+                folders.push(name);
+                self.collect_files(&item.path(), folders)?;
+                folders.pop();
+            } else if file_type.is_file() {
+                let metadata = item.metadata()?;
+                let full_path = item.path();
+                let identity = file_identity(&full_path)?;
+                if !self.store.is_stub(&full_path, identity.id, metadata.len()) {
+                    continue;
+                }
+                let usage_key = usage_key(self.root, &full_path);
+                let copied_at = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default();
+                let launched_at = self.usage.get(&usage_key);
+                self.entries.push(GameEntry {
+                    app_id: self.app_id.to_string(),
+                    path: folders.join("/"),
+                    executable_name: name,
+                    size_bytes: metadata.len(),
+                    linked: identity.links > 1,
+                    last_used: launched_at.unwrap_or(copied_at),
+                    ever_launched: launched_at.is_some(),
+                    full_path,
+                    file_id: identity.id,
+                    usage_key,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Entries matching `rule` at time `now` (Unix seconds).
+pub fn select_for_prune(entries: Vec<GameEntry>, rule: PruneRule, now: u64) -> Vec<GameEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| match rule {
+            PruneRule::NeverLaunched => !entry.ever_launched,
+            PruneRule::OlderThanDays(days) => {
+                now.saturating_sub(entry.last_used) > days.saturating_mul(SECS_PER_DAY)
+            }
+        })
+        .collect()
+}
+
+/// Deletes `entries` (as listed by [`inventory`], so only deployed stubs),
+/// skipping any whose executable is in `running`, then removes folders left
+/// empty below `root`.
+pub fn remove_entries(
+    root: &Path,
+    entries: Vec<GameEntry>,
+    running: &[PathBuf],
+    usage: &UsageLog,
+) -> CleanupReport {
+    let mut report = CleanupReport::default();
+    for entry in entries {
+        if running.iter().any(|exe| same_file(exe, &entry.full_path)) {
+            report.skipped_running.push(entry);
+            continue;
+        }
+        // Checked right before removing, so earlier removals of other links count
+        let last_link = file_identity(&entry.full_path).is_ok_and(|identity| identity.links <= 1);
+        match std::fs::remove_file(&entry.full_path) {
+            Ok(()) => {
+                if last_link {
+                    report.freed_bytes += entry.size_bytes;
+                }
+                if let Some(parent) = entry.full_path.parent() {
+                    remove_empty_dirs(root, parent);
+                }
+                report.removed.push(entry);
+            }
+            Err(e) => report.failed.push(CleanupFailure {
+                entry,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    let removed_keys: Vec<String> = report
+        .removed
+        .iter()
+        .map(|entry| entry.usage_key.clone())
+        .collect();
+    let _ = usage.forget(&removed_keys);
+    report
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    if a == b {
+        return true;
+    }
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Walks up from `dir` deleting empty folders, never touching `root` itself.
+fn remove_empty_dirs(root: &Path, dir: &Path) {
+    let mut current = Some(dir);
+    while let Some(dir) = current {
+        if dir == root || !dir.starts_with(root) || std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+        current = dir.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_stub(root: &Path, relative: &str) -> PathBuf {
+        let path = root.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"stub").unwrap();
+        path
+    }
+
+    /// A games root whose stub store holds the same bytes `write_stub` writes.
+    fn setup() -> (tempfile::TempDir, PathBuf, UsageLog) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("games");
+        write_stub(&root, ".stubs/0123abcd.exe");
+        let usage = UsageLog::load(dir.path().join("usage.json"));
+        (dir, root, usage)
+    }
+
+    #[test]
+    fn inventory_lists_nested_stubs() {
+        let (_dir, root, usage) = setup();
+        write_stub(&root, "1/bin/win64/game.exe");
+        write_stub(&root, "2/other.exe");
+        usage
+            .record(usage_key(&root, &root.join("2").join("other.exe")), 42)
+            .unwrap();
+
+        let inventory = inventory(&root, &usage).unwrap();
+        let summary: Vec<_> = inventory
+            .entries
+            .iter()
+            .map(|e| {
+                (
+                    e.app_id.as_str(),
+                    e.path.as_str(),
+                    e.executable_name.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("1", "bin/win64", "game.exe"), ("2", "", "other.exe")]
+        );
+        assert_eq!(inventory.total_bytes, 8);
+        assert!(!inventory.entries[0].ever_launched);
+        assert_eq!(inventory.entries[1].last_used, 42);
+    }
+
+    #[test]
+    fn missing_root_is_empty() {
+        let (dir, _root, usage) = setup();
+        let inventory = inventory(&dir.path().join("missing"), &usage).unwrap();
+        assert!(inventory.entries.is_empty());
+        assert_eq!(inventory.total_bytes, 0);
+    }
+
+    #[test]
+    fn prune_rules_select_expected_entries() {
+        let (_dir, root, usage) = setup();
+        write_stub(&root, "1/old.exe");
+        write_stub(&root, "2/recent.exe");
+        write_stub(&root, "3/never.exe");
+        let now = 100 * SECS_PER_DAY;
+        let old = usage_key(&root, &root.join("1").join("old.exe"));
+        let recent = usage_key(&root, &root.join("2").join("recent.exe"));
+        usage.record(old, now - 40 * SECS_PER_DAY).unwrap();
+        usage.record(recent, now - SECS_PER_DAY).unwrap();
+
+        let entries = inventory(&root, &usage).unwrap().entries;
+        let never = select_for_prune(entries.clone(), PruneRule::NeverLaunched, now);
+        assert_eq!(never.len(), 1);
+        assert_eq!(never[0].executable_name, "never.exe");
+
+        let old = select_for_prune(entries, PruneRule::OlderThanDays(30), now);
+        assert!(old.iter().any(|e| e.executable_name == "old.exe"));
+        assert!(!old.iter().any(|e| e.executable_name == "recent.exe"));
+    }
+
+    #[test]
+    fn remove_skips_running_and_cleans_empty_folders() {
+        let (_dir, root, usage) = setup();
+        let running = write_stub(&root, "1/bin/game.exe");
+        let idle = write_stub(&root, "2/deep/path/idle.exe");
+
+        let entries = inventory(&root, &usage).unwrap().entries;
+        let report = remove_entries(&root, entries, std::slice::from_ref(&running), &usage);
+
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.skipped_running.len(), 1);
+        assert_eq!(report.freed_bytes, 4);
+        assert!(running.exists());
+        assert!(!idle.exists());
+        assert!(!root.join("2").exists());
+        assert!(root.exists());
+    }
+
+    #[test]
+    fn files_that_are_not_stubs_are_left_alone() {
+        let (_dir, root, usage) = setup();
+        write_stub(&root, "1/game.exe");
+        let notes = root.join("1").join("notes.txt");
+        std::fs::write(&notes, b"keep").unwrap();
+        let other = root.join("2").join("game.exe");
+        std::fs::create_dir_all(other.parent().unwrap()).unwrap();
+        std::fs::write(&other, b"not a stub").unwrap();
+
+        let entries = inventory(&root, &usage).unwrap().entries;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].full_path, root.join("1").join("game.exe"));
+
+        remove_entries(&root, entries, &[], &usage);
+        assert!(notes.exists());
+        assert!(other.exists());
+    }
+
+    #[test]
+    fn linked_stubs_count_their_data_once() {
+        let (dir, root, usage) = setup();
+        let source = dir.path().join("src-win.exe");
+        std::fs::write(&source, b"linked stub").unwrap();
+        let deployer = crate::deploy::StubDeployer::new(&source, &root).unwrap();
+        for target in ["1/game.exe", "2/bin/game.exe"] {
+            let target = root.join(target);
+            std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+            assert!(deployer.deploy(&target).unwrap().linked);
+        }
+
+        let inventory = inventory(&root, &usage).unwrap();
+        assert_eq!(inventory.entries.len(), 2);
+        assert!(inventory.entries.iter().all(|entry| entry.linked));
+        assert_eq!(inventory.total_bytes, 11);
+
+        // The stored copy keeps the data alive
+        let report = remove_entries(&root, inventory.entries, &[], &usage);
+        assert_eq!(report.removed.len(), 2);
+        assert_eq!(report.freed_bytes, 0);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::supervisor::LaunchSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    /// The stub ended on its own, e.g. its window was closed.
+    Exited,
+    /// Closed from the stub's tray menu.
+    TrayClosed,
+    /// Stopped from the app, including when the app itself closed.
+    Stopped,
+    /// The exit could not be observed; `ended_at` is when monitoring failed.
+    Lost,
+}
+
+/// One finished run of a stub.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub app_id: String,
+    pub executable_name: String,
+    pub game_name: String,
+    pub pid: u32,
+    /// Unix time in seconds.
+    pub started_at: u64,
+    /// Unix time in seconds.
+    pub ended_at: u64,
+    pub duration_secs: u64,
+    pub exit_reason: ExitReason,
+    pub exit_code: Option<i32>,
+}
+
+impl Session {
+    pub fn new(
+        spec: &LaunchSpec,
+        pid: u32,
+        started_at: u64,
+        ended_at: u64,
+        exit_reason: ExitReason,
+        exit_code: Option<i32>,
+    ) -> Self {
+        Session {
+            app_id: spec.app_id.clone(),
+            executable_name: spec.executable_name.clone(),
+            game_name: spec.game_name.clone(),
+            pid,
+            started_at,
+            ended_at,
+            duration_secs: ended_at.saturating_sub(started_at),
+            exit_reason,
+            exit_code,
+        }
+    }
+}
+
+/// Filter for [`SessionHistory::query`]; every field is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    pub app_id: Option<String>,
+    /// Unix seconds; keeps sessions still running at or after this time.
+    pub from: Option<u64>,
+    /// Unix seconds; keeps sessions that started before this time.
+    pub to: Option<u64>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, session: &Session) -> bool {
+        self.app_id
+            .as_ref()
+            .is_none_or(|app_id| *app_id == session.app_id)
+            && self.from.is_none_or(|from| session.ended_at >= from)
+            && self.to.is_none_or(|to| session.started_at < to)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// Finished sessions, one JSON object per line in `history.jsonl`.
+///
+/// Appending keeps writes cheap however long the history gets; a line cut
+/// short by a crash is skipped when reading.
+pub struct SessionHistory {
+    path: PathBuf,
+    writing: Mutex<()>,
+    /// PIDs whose stub reported a tray close, consumed when their exit is recorded.
+    tray_closed: Mutex<HashSet<u32>>,
+}
+
+impl SessionHistory {
+    pub fn new(path: PathBuf) -> Self {
+        SessionHistory {
+            path,
+            writing: Mutex::new(()),
+            tray_closed: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn note_tray_closed(&self, pid: u32) {
+        self.tray_closed.lock().unwrap().insert(pid);
+    }
+
+    /// Exit reason for a stub that ended without an error, given whether the app asked it to stop.
+    pub fn exit_reason(&self, pid: u32, stop_requested: bool) -> ExitReason {
+        let tray_closed = self.tray_closed.lock().unwrap().remove(&pid);
+        if stop_requested {
+            ExitReason::Stopped
+        } else if tray_closed {
+            ExitReason::TrayClosed
+        } else {
+            ExitReason::Exited
+        }
+    }
+
+    pub fn record(&self, session: &Session) -> io::Result<()> {
+        let _guard = self.writing.lock().unwrap();
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_vec(session).map_err(io::Error::other)?;
+        line.push(b'\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(&line)
+    }
+
+    /// Matching sessions, oldest first. A missing file is an empty history.
+    pub fn query(&self, query: &HistoryQuery) -> io::Result<Vec<Session>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut sessions = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let Ok(session) = serde_json::from_str::<Session>(&line?) else {
+                continue;
+            };
+            if query.matches(&session) {
+                sessions.push(session);
+            }
+        }
+        sessions.sort_by_key(|session| session.started_at);
+        Ok(sessions)
+    }
+}
+
+pub fn export(sessions: &[Session], format: ExportFormat) -> io::Result<String> {
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(sessions).map_err(io::Error::other),
+        ExportFormat::Csv => Ok(to_csv(sessions)),
+    }
+}
+
+fn to_csv(sessions: &[Session]) -> String {
+    let mut csv = String::from(
+        "app_id,executable_name,game_name,pid,started_at,ended_at,duration_secs,exit_reason,exit_code\r\n",
+    );
+    for session in sessions {
+        let exit_reason = serde_json::to_value(session.exit_reason)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let fields = [
+            csv_field(&session.app_id),
+            csv_field(&session.executable_name),
+            csv_field(&session.game_name),
+            session.pid.to_string(),
+            format_utc(session.started_at),
+            format_utc(session.ended_at),
+            session.duration_secs.to_string(),
+            exit_reason,
+            session
+                .exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_default(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `YYYY-MM-DDTHH:MM:SSZ` for Unix seconds, so spreadsheets read the dates.
+fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    // Civil-from-days, Howard Hinnant's algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(app_id: &str, started_at: u64, ended_at: u64) -> Session {
+        Session {
+            app_id: app_id.to_string(),
+            executable_name: "game.exe".to_string(),
+            game_name: "Game".to_string(),
+            pid: 7,
+            started_at,
+            ended_at,
+            duration_secs: ended_at - started_at,
+            exit_reason: ExitReason::Exited,
+            exit_code: Some(0),
+        }
+    }
+
+    #[test]
+    fn records_survive_and_bad_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data").join("history.jsonl");
+        let history = SessionHistory::new(path.clone());
+        assert!(history.query(&HistoryQuery::default()).unwrap().is_empty());
+
+        history.record(&session("1", 10, 20)).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"app_id\": \"trunc\n")
+            .unwrap();
+        history.record(&session("2", 5, 8)).unwrap();
+
+        let all = SessionHistory::new(path)
+            .query(&HistoryQuery::default())
+            .unwrap();
+        assert_eq!(all, vec![session("2", 5, 8), session("1", 10, 20)]);
+    }
+
+    #[test]
+    fn query_filters_by_game_and_overlapping_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = SessionHistory::new(dir.path().join("history.jsonl"));
+        for s in [
+            session("1", 0, 100),
+            session("1", 200, 300),
+            session("2", 250, 260),
+        ] {
+            history.record(&s).unwrap();
+        }
+
+        let game = HistoryQuery {
+            app_id: Some("1".to_string()),
+            ..HistoryQuery::default()
+        };
+        assert_eq!(history.query(&game).unwrap().len(), 2);
+
+        // 50..220 overlaps the first run (still running at 50) and the second (started at 200)
+        let range = HistoryQuery {
+            from: Some(50),
+            to: Some(220),
+            ..HistoryQuery::default()
+        };
+        let starts: Vec<u64> = history
+            .query(&range)
+            .unwrap()
+            .iter()
+            .map(|s| s.started_at)
+            .collect();
+        assert_eq!(starts, vec![0, 200]);
+    }
+
+    #[test]
+    fn exit_reason_prefers_stop_then_tray() {
+        let history = SessionHistory::new(PathBuf::from("unused"));
+        history.note_tray_closed(1);
+        assert_eq!(history.exit_reason(1, false), ExitReason::TrayClosed);
+        // The note is consumed
+        assert_eq!(history.exit_reason(1, false), ExitReason::Exited);
+        history.note_tray_closed(2);
+        assert_eq!(history.exit_reason(2, true), ExitReason::Stopped);
+    }
+
+    #[test]
+    fn csv_quotes_and_formats_dates() {
+        let mut s = session("1", 1_700_000_000, 1_700_003_661);
+        s.game_name = "Half, \"Life\"".to_string();
+        s.exit_code = None;
+        let csv = export(&[s], ExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1],
+            "1,game.exe,\"Half, \"\"Life\"\"\",7,2023-11-14T22:13:20Z,2023-11-14T23:14:21Z,3661,exited,"
+        );
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(951_782_400), "2000-02-29T00:00:00Z");
+    }
+
+    #[test]
+    fn json_export_is_an_array() {
+        let json = export(&[session("1", 1, 2)], ExportFormat::Json).unwrap();
+        let parsed: Vec<Session> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, vec![session("1", 1, 2)]);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::error::CommandError;
+use crate::supervisor::{LaunchSpec, ProcessSupervisor};
+use discord_quest_core::events::QueuedLaunch;
+use discord_quest_core::ProcessKey;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// What happened to a launch request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Started(u32),
+    /// Waiting for a free slot; 1 is next in line.
+    Queued(usize),
+}
+
+struct Pending {
+    spec: LaunchSpec,
+    queued_at: u64,
+}
+
+impl Pending {
+    fn info(&self) -> QueuedLaunch {
+        QueuedLaunch {
+            app_id: self.spec.app_id.clone(),
+            executable_name: self.spec.executable_name.clone(),
+            game_name: self.spec.game_name.clone(),
+            queued_at: self.queued_at,
+        }
+    }
+}
+
+/// Keeps the number of supervised stubs at or under a limit (0 means no limit).
+///
+/// Launches go through here rather than straight to the supervisor. The limit
+/// itself is enforced by [`ProcessSupervisor::spawn_within`] as it spawns, so
+/// two requests can never both take the last slot, and the queue lock is never
+/// held across a spawn.
+#[derive(Default)]
+pub struct LaunchQueue {
+    pending: Mutex<VecDeque<Pending>>,
+}
+
+fn free_slots(supervisor: &ProcessSupervisor, limit: u32) -> usize {
+    match limit {
+        0 => usize::MAX,
+        limit => (limit as usize).saturating_sub(supervisor.snapshot().len()),
+    }
+}
+
+/// Puts `spec` at the back of the line, or reports where it already is.
+fn enqueue(pending: &mut VecDeque<Pending>, spec: LaunchSpec, now: u64) -> Admission {
+    let key = spec.key();
+    if let Some(index) = pending.iter().position(|p| p.spec.key() == key) {
+        return Admission::Queued(index + 1);
+    }
+    pending.push_back(Pending {
+        spec,
+        queued_at: now,
+    });
+    Admission::Queued(pending.len())
+}
+
+impl LaunchQueue {
+    /// Starts `spec` if there is room. Otherwise queues it when `queue` is set,
+    /// or fails with [`CommandError::LimitReached`]. A key that is already
+    /// supervised fails with [`CommandError::AlreadyRunning`] and is not queued.
+    ///
+    /// When queueing, earlier queued launches keep their turn. Without it, a
+    /// free slot is taken even if others are waiting, since this launch can't.
+    pub fn submit(
+        &self,
+        supervisor: &ProcessSupervisor,
+        spec: LaunchSpec,
+        limit: u32,
+        queue: bool,
+        now: u64,
+    ) -> Result<Admission, CommandError> {
+        {
+            let mut pending = self.pending.lock().unwrap();
+            let key = spec.key();
+            if let Some(index) = pending.iter().position(|p| p.spec.key() == key) {
+                return Ok(Admission::Queued(index + 1));
+            }
+            if supervisor.snapshot().iter().any(|p| p.key == key) {
+                return Err(CommandError::AlreadyRunning(key.to_string()));
+            }
+            if queue && free_slots(supervisor, limit) <= pending.len() {
+                return Ok(enqueue(&mut pending, spec, now));
+            }
+        }
+
+        match supervisor.spawn_within(spec.clone(), limit) {
+            Ok(pid) => Ok(Admission::Started(pid)),
+            // Another launch took the slot in the meantime
+            Err(CommandError::LimitReached(_)) if queue => {
+                Ok(enqueue(&mut self.pending.lock().unwrap(), spec, now))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Starts queued launches in order while there is room. Launches that fail
+    /// to start, including ones whose key started some other way while they
+    /// waited, are dropped from the queue and returned with their error.
+    pub fn drain(
+        &self,
+        supervisor: &ProcessSupervisor,
+        limit: u32,
+    ) -> Vec<(QueuedLaunch, LaunchSpec, Result<u32, CommandError>)> {
+        let mut started = Vec::new();
+        while free_slots(supervisor, limit) > 0 {
+            let Some(next) = self.pending.lock().unwrap().pop_front() else {
+                break;
+            };
+            let info = next.info();
+            match supervisor.spawn_within(next.spec.clone(), limit) {
+                Err(CommandError::LimitReached(_)) => {
+                    self.pending.lock().unwrap().push_front(next);
+                    break;
+                }
+                result => started.push((info, next.spec, result)),
+            }
+        }
+        started
+    }
+
+    /// Removes a queued launch; false if it was not queued.
+    pub fn cancel(&self, key: &ProcessKey) -> bool {
+        let mut pending = self.pending.lock().unwrap();
+        let before = pending.len();
+        pending.retain(|p| p.spec.key() != *key);
+        pending.len() != before
+    }
+
+    pub fn snapshot(&self) -> Vec<QueuedLaunch> {
+        self.pending
+            .lock()
+            .unwrap()
+            .iter()
+            .map(Pending::info)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::supervisor::SupervisorEvent;
+    use crate::test_support::FakeSpawner;
+    use std::sync::mpsc::Receiver;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn spec(app_id: &str, executable_name: &str) -> LaunchSpec {
+        LaunchSpec {
+            app_id: app_id.to_string(),
+            executable_name: executable_name.to_string(),
+            game_name: format!("Game {app_id}"),
+            program: "game.exe".into(),
+            args: Vec::new(),
+            current_dir: ".".into(),
+        }
+    }
+
+    fn setup() -> (
+        Arc<FakeSpawner>,
+        ProcessSupervisor,
+        Receiver<SupervisorEvent>,
+        LaunchQueue,
+    ) {
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+        (spawner, supervisor, events, LaunchQueue::default())
+    }
+
+    /// Waits until the supervisor has seen `pid` exit.
+    fn wait_exit(events: &Receiver<SupervisorEvent>, pid: u32) {
+        loop {
+            match events.recv_timeout(Duration::from_secs(2)).unwrap() {
+                SupervisorEvent::Exited { pid: exited, .. } if exited == pid => return,
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_or_queues_over_the_limit() {
+        let (_spawner, supervisor, _events, queue) = setup();
+
+        let first = queue.submit(&supervisor, spec("1", "a.exe"), 1, false, 10);
+        assert!(matches!(first, Ok(Admission::Started(_))));
+
+        let rejected = queue.submit(&supervisor, spec("2", "a.exe"), 1, false, 10);
+        assert!(matches!(rejected, Err(CommandError::LimitReached(1))));
+        assert!(queue.snapshot().is_empty());
+
+        let queued = queue.submit(&supervisor, spec("2", "a.exe"), 1, true, 10);
+        assert_eq!(queued.unwrap(), Admission::Queued(1));
+        // Asking again keeps the same place in line
+        let again = queue.submit(&supervisor, spec("2", "a.exe"), 1, true, 11);
+        assert_eq!(again.unwrap(), Admission::Queued(1));
+        assert_eq!(queue.snapshot()[0].queued_at, 10);
+    }
+
+    #[test]
+    fn free_slot_is_used_when_not_queueing() {
+        let (spawner, supervisor, events, queue) = setup();
+        let Ok(Admission::Started(pid)) = queue.submit(&supervisor, spec("1", "a.exe"), 1, true, 0)
+        else {
+            panic!("first launch should start");
+        };
+        queue
+            .submit(&supervisor, spec("2", "a.exe"), 1, true, 0)
+            .unwrap();
+        spawner.exit(pid, Ok(Some(0)));
+        wait_exit(&events, pid);
+
+        // "2" is still waiting for a drain, but the slot is free now
+        let queued = queue.submit(&supervisor, spec("3", "a.exe"), 1, true, 0);
+        assert_eq!(queued.unwrap(), Admission::Queued(2));
+        let started = queue.submit(&supervisor, spec("4", "a.exe"), 1, false, 0);
+        assert!(matches!(started, Ok(Admission::Started(_))));
+        assert!(queue.drain(&supervisor, 1).is_empty());
+        assert_eq!(queue.snapshot().len(), 2);
+    }
+
+    #[test]
+    fn supervisor_enforces_the_limit_as_it_spawns() {
+        let (_spawner, supervisor, _events, _queue) = setup();
+        supervisor.spawn_within(spec("1", "a.exe"), 1).unwrap();
+        assert!(matches!(
+            supervisor.spawn_within(spec("2", "a.exe"), 1),
+            Err(CommandError::LimitReached(1))
+        ));
+        assert!(supervisor.spawn_within(spec("2", "a.exe"), 0).is_ok());
+    }
+
+    #[test]
+    fn running_key_is_neither_started_nor_queued() {
+        let (spawner, supervisor, _events, queue) = setup();
+        queue
+            .submit(&supervisor, spec("1", "a.exe"), 2, true, 0)
+            .unwrap();
+
+        for limit in [2, 1] {
+            let again = queue.submit(&supervisor, spec("1", "a.exe"), limit, true, 0);
+            assert!(matches!(again, Err(CommandError::AlreadyRunning(_))));
+        }
+        assert!(queue.snapshot().is_empty());
+        assert_eq!(supervisor.snapshot().len(), 1);
+        assert_eq!(spawner.calls(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn zero_means_no_limit() {
+        let (_spawner, supervisor, _events, queue) = setup();
+        for app_id in ["1", "2", "3"] {
+            let admission = queue.submit(&supervisor, spec(app_id, "a.exe"), 0, false, 0);
+            assert!(matches!(admission, Ok(Admission::Started(_))));
+        }
+    }
+
+    #[test]
+    fn queued_launches_start_in_order_when_a_slot_frees() {
+        let (spawner, supervisor, events, queue) = setup();
+        let Ok(Admission::Started(pid)) = queue.submit(&supervisor, spec("1", "a.exe"), 1, true, 0)
+        else {
+            panic!("first launch should start");
+        };
+        queue
+            .submit(&supervisor, spec("2", "a.exe"), 1, true, 0)
+            .unwrap();
+        queue
+            .submit(&supervisor, spec("3", "a.exe"), 1, true, 0)
+            .unwrap();
+
+        assert!(queue.drain(&supervisor, 1).is_empty());
+
+        spawner.exit(pid, Ok(Some(0)));
+        wait_exit(&events, pid);
+        let started = queue.drain(&supervisor, 1);
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].0.app_id, "2");
+        assert!(started[0].2.is_ok());
+        assert_eq!(queue.snapshot()[0].app_id, "3");
+
+        // Raising the limit lets the rest through
+        assert_eq!(queue.drain(&supervisor, 0).len(), 1);
+        assert!(queue.snapshot().is_empty());
+    }
+
+    #[test]
+    fn failed_queued_launches_are_dropped() {
+        let (_spawner, supervisor, _events, queue) = setup();
+        queue
+            .submit(&supervisor, spec("1", "a.exe"), 1, true, 0)
+            .unwrap();
+        queue
+            .submit(&supervisor, spec("2", "missing.exe"), 1, true, 0)
+            .unwrap();
+
+        let started = queue.drain(&supervisor, 2);
+        assert_eq!(started.len(), 1);
+        assert!(started[0].2.is_err());
+        assert!(queue.snapshot().is_empty());
+    }
+
+    #[test]
+    fn cancel_removes_only_that_launch() {
+        let (_spawner, supervisor, _events, queue) = setup();
+        queue
+            .submit(&supervisor, spec("1", "a.exe"), 1, true, 0)
+            .unwrap();
+        queue
+            .submit(&supervisor, spec("2", "a.exe"), 1, true, 0)
+            .unwrap();
+        queue
+            .submit(&supervisor, spec("3", "a.exe"), 1, true, 0)
+            .unwrap();
+
+        assert!(queue.cancel(&ProcessKey::new("2", "a.exe")));
+        assert!(!queue.cancel(&ProcessKey::new("2", "a.exe")));
+        let left: Vec<String> = queue.snapshot().into_iter().map(|l| l.app_id).collect();
+        assert_eq!(left, ["3"]);
+    }
+}
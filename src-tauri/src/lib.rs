@@ -1,216 +1,762 @@
-use once_cell::sync::OnceCell;
-use std::collections::HashMap;
-use std::env;
-use std::os::windows::process::CommandExt;
-use std::path::Path;
-use std::process::Child;
-use std::sync::Mutex;
-use tauri::{path::BaseDirectory, Emitter, Manager};
+// `catalog` and `search` are public only so the benchmarks run the shipped code
+#[doc(hidden)]
+pub mod catalog;
+mod catalog_loader;
+mod deploy;
+mod error;
+mod games_dir;
+mod history;
+mod launch_queue;
+mod lifecycle;
+mod platform;
+mod process_scan;
+mod process_store;
+mod resources;
+#[doc(hidden)]
+pub mod search;
+mod settings;
+mod stub_channel;
+mod supervisor;
+#[cfg(test)]
+mod test_support;
 
-// Global process registry: maps "app_id:executable_name" -> Child
-static PROCESS_REGISTRY: OnceCell<Mutex<HashMap<String, Child>>> = OnceCell::new();
+use catalog::Catalog;
+use catalog_loader::{CatalogLoader, CatalogSummary};
+use deploy::{Deployment, StubDeployer};
+use discord_quest_core::events::{
+    QueuedLaunch, QueuedLaunchFailed, StopOutcome, StubResources, StubTrayClosed,
+    LAUNCH_QUEUE_EVENT, LIFECYCLE_EVENT, QUEUED_LAUNCH_FAILED_EVENT, STUB_RESOURCES_EVENT,
+    TRAY_CLOSED_EVENT,
+};
+use discord_quest_core::game_path::{usage_key, GamePath};
+use discord_quest_core::ProcessKey;
+use discord_quest_ipc::{Event as StubEvent, StubStatus};
+use error::CommandError;
+use games_dir::{CleanupReport, GamesInventory, PruneRule, UsageLog};
+use history::{ExitReason, ExportFormat, HistoryQuery, Session, SessionHistory};
+use launch_queue::{Admission, LaunchQueue};
+use lifecycle::lifecycle_event;
+use platform::backend;
+use process_scan::{any_running, find_adoptable, find_owned_processes, running_executables_under};
+use process_store::{ProcessRecord, ProcessStore};
+use resources::ResourceMonitor;
+use search::{SearchIndex, SearchPage};
+use serde::Serialize;
+use settings::{claim_games_dir, ExitBehavior, Settings, SettingsStore};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use supervisor::{
+    unix_now, LaunchSpec, NativeSpawner, ProcessState, ProcessSupervisor, StopRequest,
+    SupervisorEvent,
+};
+use tauri::{path::BaseDirectory, Emitter, Manager, RunEvent, State};
 
-fn get_process_registry() -> &'static Mutex<HashMap<String, Child>> {
-    PROCESS_REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+const STUB_RESOURCE: &str = "data/src-win.exe";
+const PROCESS_STATE_FILE: &str = "processes.json";
+const SETTINGS_FILE: &str = "settings.json";
+const USAGE_FILE: &str = "usage.json";
+const HISTORY_FILE: &str = "history.jsonl";
+const CATALOG_CACHE_DIR: &str = "catalog";
+
+// Extra time on exit for force-killed stubs to disappear before the app quits
+const EXIT_KILL_WAIT: Duration = Duration::from_millis(1000);
+
+// Most time on exit for the history of stopped stubs to be written
+const EXIT_HISTORY_WAIT: Duration = Duration::from_millis(2000);
+
+// How often `stub_resources` is emitted while stubs are running
+const RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Set once the app starts quitting; queued launches are no longer started
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize)]
+struct CreatedGame {
+    executable_path: PathBuf,
+    deployment: Deployment,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum LaunchOutcome {
+    Started {
+        pid: u32,
+        executable_path: PathBuf,
+    },
+    /// The concurrency limit was reached; starts when a running stub exits.
+    Queued {
+        position: usize,
+        executable_path: PathBuf,
+    },
+}
+
+/// Built on first use, so a missing resource or an unwritable games root only
+/// fails `create_fake_game` instead of the whole app. Rebuilt when the games
+/// root changes in the settings.
+#[derive(Default)]
+struct DeployerSlot(Mutex<Option<(PathBuf, Arc<StubDeployer>)>>);
+
+fn stub_deployer(
+    handle: &tauri::AppHandle,
+    slot: &DeployerSlot,
+    root: &Path,
+) -> Result<Arc<StubDeployer>, CommandError> {
+    let mut slot = slot.0.lock().unwrap();
+    if let Some((deployer_root, deployer)) = slot.as_ref() {
+        if deployer_root == root {
+            return Ok(Arc::clone(deployer));
+        }
+    }
+
+    let resource_path = handle
+        .path()
+        .resolve(STUB_RESOURCE, BaseDirectory::Resource)
+        .ok()
+        .filter(|p| p.is_file())
+        .ok_or_else(|| CommandError::ResourceMissing(STUB_RESOURCE.to_string()))?;
+    let deployer =
+        Arc::new(StubDeployer::new(&resource_path, root).map_err(CommandError::CopyFailed)?);
+    *slot = Some((root.to_path_buf(), Arc::clone(&deployer)));
+    Ok(deployer)
 }
 
 #[tauri::command(rename_all = "snake_case")]
 async fn create_fake_game(
     handle: tauri::AppHandle,
+    deployer: State<'_, DeployerSlot>,
+    settings: State<'_, SettingsStore>,
     path: &str,
     executable_name: &str,
     path_len: i64,
     app_id: String,
-) -> Result<String, String> {
+) -> Result<CreatedGame, CommandError> {
     let _ = path_len;
-    let exe_path: std::path::PathBuf = env::current_exe().unwrap_or_default();
-    let exe_dir = exe_path.parent().unwrap_or_else(|| Path::new(""));
+    let root = settings.get().games_root();
+    let game_path = GamePath::new(&root, &app_id, path, executable_name)?;
 
-    let normalized_path = Path::new(path).to_string_lossy().to_string();
+    std::fs::create_dir_all(game_path.folder()).map_err(CommandError::DirCreateFailed)?;
 
-    let game_folder_path = exe_dir
-        .join("games")
-        .join(&app_id)
-        .join(normalized_path);
-
-    match std::fs::create_dir_all(&game_folder_path) {
-        Ok(_) => {}
-        Err(e) => return Err(format!("Error al crear carpeta del juego: {}", e)),
-    };
-
-    let resource_path = handle
-        .path()
-        .resolve("data/src-win.exe", BaseDirectory::Resource)
-        .unwrap_or_default();
+    let deployment = stub_deployer(&handle, &deployer, &root)?
+        .deploy(game_path.executable())
+        .map_err(CommandError::CopyFailed)?;
 
-    let target_executable_path = game_folder_path.join(executable_name);
-    match std::fs::copy(&resource_path, &target_executable_path) {
-        Ok(_) => Ok(format!(
-            "Ejecutable creado en: {:?}",
-            target_executable_path
-        )),
-        Err(e) => Err(format!("Error al copiar ejecutable: {}", e)),
-    }
+    Ok(CreatedGame {
+        executable_path: game_path.executable().to_path_buf(),
+        deployment,
+    })
 }
 
+/// Starts a stub. Over the concurrency limit it fails with `LimitReached`, or
+/// waits in the launch queue when `queue` is true.
 #[tauri::command(rename_all = "snake_case")]
 async fn run_background_process(
     handle: tauri::AppHandle,
+    supervisor: State<'_, ProcessSupervisor>,
+    launch_queue: State<'_, LaunchQueue>,
+    usage: State<'_, UsageLog>,
+    settings: State<'_, SettingsStore>,
     name: &str,
     path: &str,
     executable_name: &str,
     path_len: i64,
     app_id: String,
-) -> Result<String, String> {
+    queue: Option<bool>,
+) -> Result<LaunchOutcome, CommandError> {
     let _ = path_len;
-    let exe_path = env::current_exe().unwrap_or_default();
-    let exe_dir = exe_path.parent().unwrap_or_else(|| Path::new(""));
-
-    let normalized_path = Path::new(path).to_string_lossy().to_string();
-
-    let game_folder_path = exe_dir
-        .join("games")
-        .join(&app_id)
-        .join(normalized_path);
-    let executable_path = game_folder_path.join(executable_name);
-
-    let args = vec!["--title".to_string(), name.to_string()];
-    // Always show window visible (no --tray flag)
-
-    match std::process::Command::new(&executable_path)
-        .args(&args)
-        .current_dir(&game_folder_path)
-        .spawn()
-    {
-        Ok(child) => {
-            let process_key = format!("{}:{}", app_id, executable_name);
-            let child_id = child.id();
-
-            // Store the child in the process registry
-            {
-                let mut registry = get_process_registry().lock().unwrap();
-                registry.insert(process_key.clone(), child);
-            }
+    let settings = settings.get();
+    let root = settings.games_root();
+    let game_path = GamePath::new(&root, &app_id, path, executable_name)?;
+
+    let args = settings.stub_args(name);
+
+    let spec = LaunchSpec {
+        app_id,
+        executable_name: executable_name.to_string(),
+        game_name: name.to_string(),
+        program: game_path.executable().to_path_buf(),
+        args: args.to_args(),
+        current_dir: game_path.folder().to_path_buf(),
+    };
 
-            // Spawn a monitoring thread that waits for the process to exit
-            let app_handle = handle.clone();
-            let key_clone = process_key.clone();
-            let app_id_clone = app_id.clone();
-            let exec_name_clone = executable_name.to_string();
-            let game_name_clone = name.to_string();
-
-            std::thread::spawn(move || {
-                loop {
-                    std::thread::sleep(std::time::Duration::from_millis(500));
-                    let mut registry = get_process_registry().lock().unwrap();
-                    if let Some(child) = registry.get_mut(&key_clone) {
-                        match child.try_wait() {
-                            Ok(Some(_status)) => {
-                                registry.remove(&key_clone);
-                                let payload = serde_json::json!({
-                                    "app_id": app_id_clone.to_string(),
-                                    "executable_name": exec_name_clone,
-                                    "game_name": game_name_clone,
-                                });
-                                let _ = app_handle.emit("process_exited", payload);
-                                break;
-                            }
-                            Ok(None) => {
-                                // Still running
-                            }
-                            Err(_) => {
-                                registry.remove(&key_clone);
-                                let payload = serde_json::json!({
-                                    "app_id": app_id_clone.to_string(),
-                                    "executable_name": exec_name_clone,
-                                    "game_name": game_name_clone,
-                                });
-                                let _ = app_handle.emit("process_exited", payload);
-                                break;
-                            }
-                        }
-                    } else {
-                        break;
-                    }
-                }
-            });
-
-            Ok(format!("Proceso iniciado (PID: {})", child_id))
+    let executable_path = game_path.executable().to_path_buf();
+    let admission = launch_queue.submit(
+        &supervisor,
+        spec,
+        settings.max_concurrent_stubs,
+        queue.unwrap_or(false),
+        unix_now(),
+    )?;
+    match admission {
+        Admission::Started(pid) => {
+            let _ = usage.record(usage_key(&root, &executable_path), unix_now());
+            Ok(LaunchOutcome::Started {
+                pid,
+                executable_path,
+            })
+        }
+        Admission::Queued(position) => {
+            let _ = handle.emit(LAUNCH_QUEUE_EVENT, launch_queue.snapshot());
+            Ok(LaunchOutcome::Queued {
+                position,
+                executable_path,
+            })
         }
-        Err(e) => Err(format!("Error al iniciar proceso: {}", e)),
     }
 }
 
+/// Launches waiting for a free slot, next first.
 #[tauri::command(rename_all = "snake_case")]
-async fn stop_process(exec_name: String, app_id: Option<String>) -> Result<(), String> {
-    if let Some(app_id_str) = &app_id {
-        let process_key = format!("{}:{}", app_id_str, exec_name);
+fn get_launch_queue(launch_queue: State<'_, LaunchQueue>) -> Vec<QueuedLaunch> {
+    launch_queue.snapshot()
+}
 
-        // Extract child from registry quickly, then release lock
-        let child_opt = {
-            let mut registry = get_process_registry().lock().unwrap();
-            registry.remove(&process_key)
-        };
+/// Drops a queued launch; false if it was not queued (e.g. it already started).
+#[tauri::command(rename_all = "snake_case")]
+fn cancel_queued_launch(
+    handle: tauri::AppHandle,
+    launch_queue: State<'_, LaunchQueue>,
+    app_id: String,
+    executable_name: String,
+) -> bool {
+    let cancelled = launch_queue.cancel(&ProcessKey::new(&app_id, &executable_name));
+    if cancelled {
+        let _ = handle.emit(LAUNCH_QUEUE_EVENT, launch_queue.snapshot());
+    }
+    cancelled
+}
 
-        if let Some(mut child) = child_opt {
-            let pid = child.id();
+/// Starts queued launches that now fit under the limit.
+fn start_queued_launches(handle: &tauri::AppHandle) {
+    if SHUTTING_DOWN.load(Ordering::SeqCst) {
+        return;
+    }
+    let settings = handle.state::<SettingsStore>().get();
+    let launch_queue = handle.state::<LaunchQueue>();
+    let started = launch_queue.drain(
+        &handle.state::<ProcessSupervisor>(),
+        settings.max_concurrent_stubs,
+    );
+    if started.is_empty() {
+        return;
+    }
 
-            // 1) Graceful: taskkill without /F sends WM_CLOSE (non-blocking spawn)
-            let _ = std::process::Command::new("taskkill")
-                .arg("/PID")
-                .arg(pid.to_string())
-                .creation_flags(0x08000000)
-                .spawn();
+    let root = settings.games_root();
+    for (launch, spec, result) in started {
+        match result {
+            Ok(_) => {
+                let key = usage_key(&root, &spec.program);
+                let _ = handle.state::<UsageLog>().record(key, unix_now());
+            }
+            Err(e) => {
+                let payload = QueuedLaunchFailed {
+                    launch,
+                    error: e.to_string(),
+                };
+                let _ = handle.emit(QUEUED_LAUNCH_FAILED_EVENT, payload);
+            }
+        }
+    }
+    let _ = handle.emit(LAUNCH_QUEUE_EVENT, launch_queue.snapshot());
+}
 
-            // 2) Background thread handles wait + force-kill fallback (no UI blocking)
-            std::thread::spawn(move || {
-                for _ in 0..15 {
-                    std::thread::sleep(std::time::Duration::from_millis(100));
-                    if let Ok(Some(_)) = child.try_wait() {
-                        return;
-                    }
-                }
-                let _ = child.kill();
-                let _ = child.wait();
-            });
+/// Closes a stub and waits until it is gone. `timeout_ms` overrides the grace
+/// period from the settings before the force-kill.
+#[tauri::command(rename_all = "snake_case")]
+async fn stop_process(
+    supervisor: State<'_, ProcessSupervisor>,
+    settings: State<'_, SettingsStore>,
+    exec_name: String,
+    app_id: Option<String>,
+    timeout_ms: Option<u64>,
+) -> Result<StopOutcome, CommandError> {
+    let settings = settings.get();
+    let grace = timeout_ms.map_or(settings.stop_grace(), Duration::from_millis);
+    let root = settings.games_root();
+    let supervisor = supervisor.inner().clone();
+    // Waiting blocks, so keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        stop_and_wait(&supervisor, &root, &exec_name, app_id.as_deref(), grace)
+    })
+    .await
+    .unwrap_or(Ok(StopOutcome::Failed))
+}
 
-            return Ok(());
+fn stop_and_wait(
+    supervisor: &ProcessSupervisor,
+    root: &Path,
+    exec_name: &str,
+    app_id: Option<&str>,
+    grace: Duration,
+) -> Result<StopOutcome, CommandError> {
+    if let Some(app_id) = app_id {
+        let key = ProcessKey::new(app_id, exec_name);
+        if let Some(outcome) = supervisor.stop_wait(&key, grace, EXIT_KILL_WAIT) {
+            return Ok(outcome);
         }
+    }
 
-        // Not in registry — force kill by executable name (non-blocking)
-        let _ = std::process::Command::new("taskkill")
-            .args(&["/F", "/IM", &exec_name])
-            .creation_flags(0x08000000)
-            .spawn();
-        return Ok(());
-    }
-
-    // No app_id: force kill by image name (non-blocking)
-    let _ = std::process::Command::new("taskkill")
-        .args(&["/F", "/IM", &exec_name])
-        .creation_flags(0x08000000)
-        .spawn();
-    Ok(())
-}
-
-#[tauri::command(rename_all = "snake_case")]
-fn get_active_processes() -> Vec<serde_json::Value> {
-    let registry = get_process_registry().lock().unwrap();
-    registry
-        .keys()
-        .map(|key| {
-            let parts: Vec<&str> = key.splitn(2, ':').collect();
-            serde_json::json!({
-                "app_id": parts.get(0).unwrap_or(&""),
-                "executable_name": parts.get(1).unwrap_or(&""),
-                "key": key,
-            })
+    // Not supervised (e.g. started by a previous session): only kill copies that
+    // live in our own games/ tree, never every process with that image name
+    let scope = match app_id {
+        Some(app_id) => GamePath::new(root, app_id, "", exec_name)?
+            .folder()
+            .to_path_buf(),
+        None => root.to_path_buf(),
+    };
+
+    let pids = find_owned_processes(&scope, exec_name);
+    if pids.is_empty() {
+        return Err(CommandError::NotOwned(exec_name.to_string()));
+    }
+    for &pid in &pids {
+        let _ = backend().force_kill(pid);
+    }
+
+    let deadline = Instant::now() + EXIT_KILL_WAIT;
+    while any_running(&pids) {
+        if Instant::now() >= deadline {
+            return Ok(StopOutcome::Failed);
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Ok(StopOutcome::Forced)
+}
+
+/// A supervised stub as shown in the UI.
+#[derive(Debug, Serialize)]
+struct ActiveProcess {
+    key: ProcessKey,
+    app_id: String,
+    executable_name: String,
+    game_name: String,
+    pid: u32,
+    /// Unix time in seconds.
+    started_at: u64,
+    uptime_secs: u64,
+    state: ProcessState,
+    executable_path: PathBuf,
+}
+
+/// Supervised stubs, oldest first.
+#[tauri::command(rename_all = "snake_case")]
+fn get_active_processes(supervisor: State<'_, ProcessSupervisor>) -> Vec<ActiveProcess> {
+    let now = unix_now();
+    let mut processes = supervisor.snapshot();
+    processes.sort_by(|a, b| (a.started_at, &a.key).cmp(&(b.started_at, &b.key)));
+    processes
+        .into_iter()
+        .map(|process| ActiveProcess {
+            uptime_secs: process.uptime_secs(now),
+            key: process.key,
+            app_id: process.spec.app_id,
+            executable_name: process.spec.executable_name,
+            game_name: process.spec.game_name,
+            pid: process.pid,
+            started_at: process.started_at,
+            state: process.state,
+            executable_path: process.spec.program,
         })
         .collect()
 }
 
+fn sample_resources(
+    supervisor: &ProcessSupervisor,
+    monitor: &ResourceMonitor,
+) -> Vec<StubResources> {
+    let processes: Vec<(ProcessKey, u32)> = supervisor
+        .snapshot()
+        .into_iter()
+        .map(|process| (process.key, process.pid))
+        .collect();
+    if processes.is_empty() {
+        return Vec::new();
+    }
+    monitor.sample(&processes, unix_now())
+}
+
+/// Memory, CPU and handle/thread counts of every supervised stub.
+#[tauri::command(rename_all = "snake_case")]
+async fn get_stub_resources(
+    supervisor: State<'_, ProcessSupervisor>,
+    monitor: State<'_, ResourceMonitor>,
+) -> Result<Vec<StubResources>, CommandError> {
+    Ok(sample_resources(&supervisor, &monitor))
+}
 
+/// Emits `stub_resources` every few seconds while stubs are running.
+fn report_resources(handle: tauri::AppHandle) {
+    loop {
+        std::thread::sleep(RESOURCE_SAMPLE_INTERVAL);
+        let samples = sample_resources(
+            &handle.state::<ProcessSupervisor>(),
+            &handle.state::<ResourceMonitor>(),
+        );
+        if !samples.is_empty() {
+            let _ = handle.emit(STUB_RESOURCES_EVENT, samples);
+        }
+    }
+}
 
+fn supervised_pid(
+    supervisor: &ProcessSupervisor,
+    app_id: &str,
+    executable_name: &str,
+) -> Result<u32, CommandError> {
+    let key = ProcessKey::new(app_id, executable_name);
+    supervisor
+        .snapshot()
+        .into_iter()
+        .find(|process| process.key == key)
+        .map(|process| process.pid)
+        .ok_or_else(|| CommandError::NotRunning(key.to_string()))
+}
+
+/// Title and elapsed time as reported by the stub itself.
+#[tauri::command(rename_all = "snake_case")]
+async fn get_stub_status(
+    supervisor: State<'_, ProcessSupervisor>,
+    app_id: String,
+    executable_name: String,
+) -> Result<StubStatus, CommandError> {
+    let pid = supervised_pid(&supervisor, &app_id, &executable_name)?;
+    stub_channel::status(pid).map_err(CommandError::StubUnreachable)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn show_stub_window(
+    supervisor: State<'_, ProcessSupervisor>,
+    app_id: String,
+    executable_name: String,
+) -> Result<(), CommandError> {
+    let pid = supervised_pid(&supervisor, &app_id, &executable_name)?;
+    stub_channel::show_window(pid).map_err(CommandError::StubUnreachable)
+}
+
+/// Executables under `root` that must not be deleted: supervised stubs plus any
+/// other live process started from the games tree.
+fn running_stubs(supervisor: &ProcessSupervisor, root: &Path) -> Vec<PathBuf> {
+    let mut running: Vec<PathBuf> = supervisor
+        .snapshot()
+        .into_iter()
+        .map(|process| process.spec.program)
+        .collect();
+    running.extend(running_executables_under(root));
+    running
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn get_games_inventory(
+    usage: State<'_, UsageLog>,
+    settings: State<'_, SettingsStore>,
+) -> Result<GamesInventory, CommandError> {
+    games_dir::inventory(&settings.get().games_root(), &usage)
+        .map_err(CommandError::InventoryFailed)
+}
+
+/// Removes stubs never launched, or, with `older_than_days`, stubs unused for that long.
+#[tauri::command(rename_all = "snake_case")]
+async fn prune_games(
+    supervisor: State<'_, ProcessSupervisor>,
+    usage: State<'_, UsageLog>,
+    settings: State<'_, SettingsStore>,
+    older_than_days: Option<u64>,
+) -> Result<CleanupReport, CommandError> {
+    let root = settings.get().games_root();
+    let rule = match older_than_days {
+        Some(days) => PruneRule::OlderThanDays(days),
+        None => PruneRule::NeverLaunched,
+    };
+    let entries = games_dir::inventory(&root, &usage)
+        .map_err(CommandError::InventoryFailed)?
+        .entries;
+    let selected = games_dir::select_for_prune(entries, rule, unix_now());
+    let running = running_stubs(&supervisor, &root);
+    Ok(games_dir::remove_entries(&root, selected, &running, &usage))
+}
+
+/// Deletes every deployed stub that is not currently running; other files are kept.
+#[tauri::command(rename_all = "snake_case")]
+async fn reset_games(
+    supervisor: State<'_, ProcessSupervisor>,
+    usage: State<'_, UsageLog>,
+    settings: State<'_, SettingsStore>,
+) -> Result<CleanupReport, CommandError> {
+    let root = settings.get().games_root();
+    let entries = games_dir::inventory(&root, &usage)
+        .map_err(CommandError::InventoryFailed)?
+        .entries;
+    let running = running_stubs(&supervisor, &root);
+    Ok(games_dir::remove_entries(&root, entries, &running, &usage))
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_settings(settings: State<'_, SettingsStore>) -> Settings {
+    settings.get()
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn update_settings(
+    handle: tauri::AppHandle,
+    settings: State<'_, SettingsStore>,
+    new_settings: Settings,
+) -> Result<Settings, CommandError> {
+    new_settings.validate()?;
+    claim_games_dir(&new_settings).map_err(CommandError::SettingsSaveFailed)?;
+    let saved = settings
+        .update(new_settings)
+        .map_err(CommandError::SettingsSaveFailed)?;
+    error::set_message_lang(saved.language);
+    // A higher limit may let queued launches through
+    start_queued_launches(&handle);
+    Ok(saved)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn get_session_history(
+    history: State<'_, SessionHistory>,
+    query: HistoryQuery,
+) -> Result<Vec<Session>, CommandError> {
+    history.query(&query).map_err(CommandError::HistoryFailed)
+}
+
+/// Writes the sessions matching `query` to `path`; returns how many were written.
+#[tauri::command(rename_all = "snake_case")]
+async fn export_session_history(
+    history: State<'_, SessionHistory>,
+    query: HistoryQuery,
+    format: ExportFormat,
+    path: PathBuf,
+) -> Result<usize, CommandError> {
+    let sessions = history.query(&query).map_err(CommandError::HistoryFailed)?;
+    let contents = history::export(&sessions, format).map_err(CommandError::HistoryFailed)?;
+    std::fs::write(&path, contents).map_err(CommandError::HistoryFailed)?;
+    Ok(sessions.len())
+}
+
+/// Checks a catalog document; malformed entries are skipped and listed in `issues`.
+#[tauri::command(rename_all = "snake_case")]
+async fn parse_catalog(contents: String) -> Result<Catalog, CommandError> {
+    catalog::parse(contents.as_bytes()).map_err(CommandError::InvalidCatalog)
+}
+
+/// Where the last verified catalog download is kept.
+struct CatalogCacheDir(PathBuf);
+
+/// Search index over the last catalog returned by `load_catalog`.
+#[derive(Default)]
+struct CatalogSearch(RwLock<Option<Arc<SearchIndex>>>);
+
+/// Downloads the catalog from the mirror, verified against its published sha256,
+/// or uses the saved copy when it is current or the mirror can't be reached.
+/// The games go into the index used by `search_catalog`; only a summary is returned.
+#[tauri::command(rename_all = "snake_case")]
+async fn load_catalog(
+    cache_dir: State<'_, CatalogCacheDir>,
+    catalog_search: State<'_, CatalogSearch>,
+) -> Result<CatalogSummary, CommandError> {
+    let loader =
+        CatalogLoader::new(cache_dir.0.clone()).map_err(CommandError::CatalogUnavailable)?;
+    // The loader blocks on the network, so keep it off the async runtime
+    let (summary, index) = tauri::async_runtime::spawn_blocking(move || {
+        let (games, summary) = loader.load(unix_now())?.into_games();
+        Ok::<_, std::io::Error>((summary, SearchIndex::build(games)))
+    })
+    .await
+    .map_err(|e| CommandError::CatalogUnavailable(std::io::Error::other(e)))?
+    .map_err(CommandError::CatalogUnavailable)?;
+    *catalog_search.0.write().unwrap() = Some(Arc::new(index));
+    Ok(summary)
+}
+
+/// Games whose name or alias matches `query`, best first. `limit` defaults to
+/// [`search::DEFAULT_PAGE_SIZE`] and is capped at [`search::MAX_PAGE_SIZE`].
+#[tauri::command(rename_all = "snake_case")]
+async fn search_catalog(
+    catalog_search: State<'_, CatalogSearch>,
+    query: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<SearchPage, CommandError> {
+    let index = catalog_search
+        .0
+        .read()
+        .unwrap()
+        .clone()
+        .ok_or(CommandError::CatalogNotLoaded)?;
+    Ok(index.search(
+        &query,
+        offset.unwrap_or(0),
+        limit.unwrap_or(search::DEFAULT_PAGE_SIZE),
+    ))
+}
+
+/// Stubs from a previous session that were still alive at startup and got re-adopted.
+struct RecoveredProcesses(Vec<ProcessRecord>);
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_recovered_processes(recovered: State<'_, RecoveredProcesses>) -> Vec<ProcessRecord> {
+    recovered.0.clone()
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn stop_recovered_processes(
+    supervisor: State<'_, ProcessSupervisor>,
+    recovered: State<'_, RecoveredProcesses>,
+    settings: State<'_, SettingsStore>,
+) -> Vec<ProcessKey> {
+    let grace = settings.get().stop_grace();
+    recovered
+        .0
+        .iter()
+        .filter(|record| {
+            matches!(
+                supervisor.stop(&record.key, grace),
+                StopRequest::Stopping(pid) if pid == record.pid
+            )
+        })
+        .map(|record| record.key.clone())
+        .collect()
+}
+
+fn recover_processes(
+    supervisor: &ProcessSupervisor,
+    store: &ProcessStore,
+    root: &Path,
+) -> Vec<ProcessRecord> {
+    let recovered: Vec<ProcessRecord> = store
+        .load()
+        .into_iter()
+        .filter(|record| match find_adoptable(record, root) {
+            Some(process) => supervisor
+                .adopt(record.to_spec(), Box::new(process), record.started_at)
+                .is_ok(),
+            None => false,
+        })
+        .collect();
+    // Drop records of stubs that are gone so they are not checked again next time
+    let _ = store.save(&recovered);
+    recovered
+}
+
+/// Closes every stub when the app quits, unless the user chose to keep them running.
+fn shutdown_stubs(handle: &tauri::AppHandle) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let settings = handle.state::<SettingsStore>().get();
+    if settings.exit_behavior == ExitBehavior::KeepStubs {
+        return;
+    }
+    let supervisor = handle.state::<ProcessSupervisor>();
+    supervisor.stop_all(settings.stop_grace(), EXIT_KILL_WAIT);
+    // The sessions are recorded by the event forwarder; don't quit before it gets to them
+    handle
+        .state::<HandledEvents>()
+        .wait_for(supervisor.events_sent(), EXIT_HISTORY_WAIT);
+}
+
+/// How many supervisor events the forwarder has finished handling.
+#[derive(Default)]
+struct HandledEvents {
+    count: Mutex<u64>,
+    changed: Condvar,
+}
+
+impl HandledEvents {
+    fn bump(&self) {
+        *self.count.lock().unwrap() += 1;
+        self.changed.notify_all();
+    }
+
+    /// Blocks until `target` events were handled or `timeout` passes.
+    fn wait_for(&self, target: u64, timeout: Duration) {
+        let count = self.count.lock().unwrap();
+        let _ = self
+            .changed
+            .wait_timeout_while(count, timeout, |count| *count < target);
+    }
+}
+
+/// Relays what a stub reports about itself, e.g. being closed from its tray.
+fn watch_stub(handle: tauri::AppHandle, spec: LaunchSpec, pid: u32) {
+    stub_channel::watch(pid, move |event| match event {
+        StubEvent::TrayClosed => {
+            handle.state::<SessionHistory>().note_tray_closed(pid);
+            let payload = StubTrayClosed {
+                app_id: spec.app_id.clone(),
+                executable_name: spec.executable_name.clone(),
+                game_name: spec.game_name.clone(),
+            };
+            let _ = handle.emit(TRAY_CLOSED_EVENT, payload);
+        }
+    });
+}
+
+/// Forwards supervisor events to the webview until the supervisor is dropped.
+fn forward_supervisor_events(
+    handle: tauri::AppHandle,
+    events: Receiver<SupervisorEvent>,
+    store: ProcessStore,
+) {
+    for event in events {
+        handle_supervisor_event(&handle, event, &store);
+        handle.state::<HandledEvents>().bump();
+    }
+}
+
+fn handle_supervisor_event(
+    handle: &tauri::AppHandle,
+    event: SupervisorEvent,
+    store: &ProcessStore,
+) {
+    // Keep the state file in sync so a crash never loses track of running stubs
+    let records: Vec<ProcessRecord> = handle
+        .state::<ProcessSupervisor>()
+        .snapshot()
+        .iter()
+        .map(ProcessRecord::from_process)
+        .collect();
+    let _ = store.save(&records);
+
+    let now = unix_now();
+    let _ = handle.emit(LIFECYCLE_EVENT, lifecycle_event(&event, now));
+
+    let history = handle.state::<SessionHistory>();
+    let session = match event {
+        SupervisorEvent::Started { spec, pid, .. } => {
+            watch_stub(handle.clone(), spec, pid);
+            return;
+        }
+        SupervisorEvent::StopRequested { .. }
+        | SupervisorEvent::ForceKilled { .. }
+        | SupervisorEvent::StopFinished { .. } => return,
+        SupervisorEvent::Exited {
+            spec,
+            pid,
+            started_at,
+            exit_code,
+            stop_requested,
+        } => {
+            let reason = history.exit_reason(pid, stop_requested);
+            Session::new(&spec, pid, started_at, now, reason, exit_code)
+        }
+        SupervisorEvent::WaitFailed {
+            spec,
+            pid,
+            started_at,
+            ..
+        } => {
+            // Drops a pending tray note so a reused PID can't inherit it
+            let _ = history.exit_reason(pid, false);
+            Session::new(&spec, pid, started_at, now, ExitReason::Lost, None)
+        }
+    };
+    let _ = history.record(&session);
+    start_queued_launches(handle);
+}
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -218,12 +764,59 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            let settings = SettingsStore::load(app.path().app_config_dir()?.join(SETTINGS_FILE));
+            error::set_message_lang(settings.get().language);
+            let (supervisor, events) = ProcessSupervisor::new(Arc::new(NativeSpawner));
+            let data_dir = app.path().app_local_data_dir()?;
+            let store = ProcessStore::new(data_dir.join(PROCESS_STATE_FILE));
+            let recovered = recover_processes(&supervisor, &store, &settings.get().games_root());
+            app.manage(supervisor);
+            app.manage(settings);
+            app.manage(RecoveredProcesses(recovered));
+            app.manage(UsageLog::load(data_dir.join(USAGE_FILE)));
+            app.manage(DeployerSlot::default());
+            app.manage(LaunchQueue::default());
+            app.manage(ResourceMonitor::default());
+            app.manage(SessionHistory::new(data_dir.join(HISTORY_FILE)));
+            app.manage(HandledEvents::default());
+            app.manage(CatalogCacheDir(data_dir.join(CATALOG_CACHE_DIR)));
+            app.manage(CatalogSearch::default());
+
+            let handle = app.handle().clone();
+            std::thread::spawn(move || forward_supervisor_events(handle, events, store));
+            let handle = app.handle().clone();
+            std::thread::spawn(move || report_resources(handle));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             create_fake_game,
             stop_process,
             run_background_process,
-            get_active_processes
+            get_launch_queue,
+            cancel_queued_launch,
+            get_active_processes,
+            get_stub_resources,
+            get_stub_status,
+            show_stub_window,
+            get_recovered_processes,
+            stop_recovered_processes,
+            get_session_history,
+            export_session_history,
+            parse_catalog,
+            load_catalog,
+            search_catalog,
+            get_settings,
+            update_settings,
+            get_games_inventory,
+            prune_games,
+            reset_games
         ])
-        .run(tauri::generate_context!())
-        .expect("Error al ejecutar la aplicación");
+        .build(tauri::generate_context!())
+        .expect("Error al ejecutar la aplicación")
+        .run(|handle, event| {
+            if let RunEvent::ExitRequested { .. } | RunEvent::Exit = event {
+                shutdown_stubs(handle);
+            }
+        });
 }
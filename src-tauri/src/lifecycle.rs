@@ -0,0 +1,170 @@
+use crate::supervisor::{LaunchSpec, SupervisorEvent};
+use discord_quest_core::events::{LifecycleEvent, ProcessInfo};
+
+/// The webview's view of a supervisor event that happened at `now`.
+pub fn lifecycle_event(event: &SupervisorEvent, now: u64) -> LifecycleEvent {
+    let info = |spec: &LaunchSpec, pid: u32, started_at: u64| ProcessInfo {
+        app_id: spec.app_id.clone(),
+        executable_name: spec.executable_name.clone(),
+        game_name: spec.game_name.clone(),
+        pid,
+        timestamp: now,
+        duration_secs: now.saturating_sub(started_at),
+    };
+    match event {
+        SupervisorEvent::Started {
+            spec,
+            pid,
+            started_at,
+        } => LifecycleEvent::Started {
+            process: info(spec, *pid, *started_at),
+        },
+        SupervisorEvent::StopRequested {
+            spec,
+            pid,
+            started_at,
+        } => LifecycleEvent::StopRequested {
+            process: info(spec, *pid, *started_at),
+        },
+        SupervisorEvent::ForceKilled {
+            spec,
+            pid,
+            started_at,
+        } => LifecycleEvent::ForceKilled {
+            process: info(spec, *pid, *started_at),
+        },
+        SupervisorEvent::StopFinished {
+            spec,
+            pid,
+            started_at,
+            outcome,
+        } => LifecycleEvent::StopFinished {
+            process: info(spec, *pid, *started_at),
+            outcome: *outcome,
+        },
+        SupervisorEvent::Exited {
+            spec,
+            pid,
+            started_at,
+            exit_code,
+            ..
+        } => LifecycleEvent::Exited {
+            process: info(spec, *pid, *started_at),
+            exit_code: *exit_code,
+        },
+        SupervisorEvent::WaitFailed {
+            spec,
+            pid,
+            started_at,
+            error,
+            ..
+        } => LifecycleEvent::MonitorError {
+            process: info(spec, *pid, *started_at),
+            error: error.clone(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use discord_quest_core::events::StopOutcome;
+    use std::path::PathBuf;
+
+    fn spec() -> LaunchSpec {
+        LaunchSpec {
+            app_id: "1".to_string(),
+            executable_name: "game.exe".to_string(),
+            game_name: "Game".to_string(),
+            program: PathBuf::from("game.exe"),
+            args: Vec::new(),
+            current_dir: PathBuf::new(),
+        }
+    }
+
+    #[test]
+    fn exit_serializes_flat_with_duration() {
+        let event = SupervisorEvent::Exited {
+            spec: spec(),
+            pid: 9,
+            started_at: 1_000,
+            exit_code: Some(1),
+            stop_requested: false,
+        };
+        let json = serde_json::to_value(lifecycle_event(&event, 1_090)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "exited",
+                "app_id": "1",
+                "executable_name": "game.exe",
+                "game_name": "Game",
+                "pid": 9,
+                "timestamp": 1_090,
+                "duration_secs": 90,
+                "exit_code": 1,
+            })
+        );
+    }
+
+    #[test]
+    fn adopted_start_keeps_its_uptime() {
+        let event = SupervisorEvent::Started {
+            spec: spec(),
+            pid: 9,
+            started_at: 1_000,
+        };
+        let json = serde_json::to_value(lifecycle_event(&event, 4_600)).unwrap();
+        assert_eq!(json["kind"], "started");
+        assert_eq!(json["duration_secs"], 3_600);
+    }
+
+    #[test]
+    fn every_supervisor_event_has_a_kind() {
+        let events = [
+            SupervisorEvent::Started {
+                spec: spec(),
+                pid: 1,
+                started_at: 0,
+            },
+            SupervisorEvent::StopRequested {
+                spec: spec(),
+                pid: 1,
+                started_at: 0,
+            },
+            SupervisorEvent::ForceKilled {
+                spec: spec(),
+                pid: 1,
+                started_at: 0,
+            },
+            SupervisorEvent::StopFinished {
+                spec: spec(),
+                pid: 1,
+                started_at: 0,
+                outcome: StopOutcome::Forced,
+            },
+            SupervisorEvent::WaitFailed {
+                spec: spec(),
+                pid: 1,
+                started_at: 0,
+                error: "boom".to_string(),
+                stop_requested: false,
+            },
+        ];
+        let kinds: Vec<serde_json::Value> = events
+            .iter()
+            .map(|event| serde_json::to_value(lifecycle_event(event, 5)).unwrap())
+            .map(|json| json["kind"].clone())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "started",
+                "stop_requested",
+                "force_killed",
+                "stop_finished",
+                "monitor_error"
+            ]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use std::io;
+use std::process::{Child, Command};
+
+#[cfg(unix)]
+mod unix;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(unix)]
+pub use unix::file_identity;
+#[cfg(unix)]
+pub use unix::UnixBackend as NativeBackend;
+#[cfg(windows)]
+pub use windows::file_identity;
+#[cfg(windows)]
+pub use windows::thread_counts;
+#[cfg(windows)]
+pub use windows::WindowsBackend as NativeBackend;
+
+/// OS-specific process control used by the commands.
+///
+/// `terminate` asks the process to close on its own (WM_CLOSE / SIGTERM),
+/// `force_kill` ends it without giving it a chance to clean up. Both take a PID,
+/// so callers must only pass children that have not been reaped yet, otherwise
+/// the PID may already belong to another process.
+pub trait ProcessBackend: Send + Sync {
+    fn spawn(&self, command: &mut Command) -> io::Result<Child>;
+
+    fn terminate(&self, pid: u32) -> io::Result<()>;
+
+    fn force_kill(&self, pid: u32) -> io::Result<()>;
+}
+
+pub fn backend() -> &'static dyn ProcessBackend {
+    static BACKEND: NativeBackend = NativeBackend;
+    &BACKEND
+}
+
+/// Where a file's data lives, shared by all its hard links.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileId {
+    /// Device on Unix, volume serial number on Windows.
+    pub volume: u64,
+    /// Inode on Unix, file index on Windows.
+    pub index: u64,
+}
+
+/// A file's [`FileId`] plus how many names point at its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileIdentity {
+    pub id: FileId,
+    pub links: u64,
+}
@@ -0,0 +1,82 @@
+use super::{FileId, FileIdentity, ProcessBackend};
+use std::io;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::process::{Child, Command};
+
+pub struct UnixBackend;
+
+impl ProcessBackend for UnixBackend {
+    fn spawn(&self, command: &mut Command) -> io::Result<Child> {
+        command.spawn()
+    }
+
+    fn terminate(&self, pid: u32) -> io::Result<()> {
+        send_signal(pid, libc::SIGTERM)
+    }
+
+    fn force_kill(&self, pid: u32) -> io::Result<()> {
+        send_signal(pid, libc::SIGKILL)
+    }
+}
+
+fn send_signal(pid: u32, signal: libc::c_int) -> io::Result<()> {
+    let pid = libc::pid_t::try_from(pid)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "PID out of range"))?;
+    // SAFETY: kill(2) has no memory-safety requirements
+    if unsafe { libc::kill(pid, signal) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+pub fn file_identity(path: &Path) -> io::Result<FileIdentity> {
+    let metadata = std::fs::metadata(path)?;
+    Ok(FileIdentity {
+        id: FileId {
+            volume: metadata.dev(),
+            index: metadata.ino(),
+        },
+        links: metadata.nlink(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn wait_for_exit(child: &mut Child) -> Option<std::process::ExitStatus> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if let Some(status) = child.try_wait().unwrap() {
+                return Some(status);
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        None
+    }
+
+    #[test]
+    fn terminate_sends_sigterm() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let backend = UnixBackend;
+        let mut child = backend.spawn(Command::new("sleep").arg("30")).unwrap();
+        backend.terminate(child.id()).unwrap();
+        let status = wait_for_exit(&mut child).expect("child did not exit");
+        assert_eq!(status.signal(), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn force_kill_sends_sigkill() {
+        use std::os::unix::process::ExitStatusExt;
+
+        let backend = UnixBackend;
+        let mut child = backend.spawn(Command::new("sleep").arg("30")).unwrap();
+        backend.force_kill(child.id()).unwrap();
+        let status = wait_for_exit(&mut child).expect("child did not exit");
+        assert_eq!(status.signal(), Some(libc::SIGKILL));
+    }
+}
@@ -0,0 +1,88 @@
+use super::{FileId, FileIdentity, ProcessBackend};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::os::windows::io::AsRawHandle;
+use std::os::windows::process::CommandExt;
+use std::path::Path;
+use std::process::{Child, Command};
+use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+use windows_sys::Win32::Storage::FileSystem::{
+    GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION,
+};
+use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+};
+
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+pub struct WindowsBackend;
+
+impl ProcessBackend for WindowsBackend {
+    fn spawn(&self, command: &mut Command) -> io::Result<Child> {
+        command.spawn()
+    }
+
+    fn terminate(&self, pid: u32) -> io::Result<()> {
+        // taskkill without /F sends WM_CLOSE (non-blocking spawn)
+        Command::new("taskkill")
+            .arg("/PID")
+            .arg(pid.to_string())
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn()
+            .map(|_| ())
+    }
+
+    fn force_kill(&self, pid: u32) -> io::Result<()> {
+        Command::new("taskkill")
+            .args(["/F", "/PID", &pid.to_string()])
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn()
+            .map(|_| ())
+    }
+}
+
+/// Number of threads of every process, from one Toolhelp snapshot.
+///
+/// The snapshot always covers the whole system, so the counts are gathered
+/// once per sample instead of once per process.
+pub fn thread_counts() -> io::Result<HashMap<u32, u64>> {
+    // SAFETY: the snapshot handle is checked and closed below, and the entry
+    // passed to Thread32First/Next has its dwSize set as the API requires
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut counts = HashMap::new();
+        let mut entry = THREADENTRY32 {
+            dwSize: std::mem::size_of::<THREADENTRY32>() as u32,
+            ..Default::default()
+        };
+        let mut found = Thread32First(snapshot, &mut entry) != 0;
+        while found {
+            *counts.entry(entry.th32OwnerProcessID).or_insert(0) += 1;
+            found = Thread32Next(snapshot, &mut entry) != 0;
+        }
+        CloseHandle(snapshot);
+        Ok(counts)
+    }
+}
+
+/// `std` only exposes the file index and link count on nightly.
+pub fn file_identity(path: &Path) -> io::Result<FileIdentity> {
+    let file = File::open(path)?;
+    let mut info = BY_HANDLE_FILE_INFORMATION::default();
+    // SAFETY: the handle stays open for the call and `info` is a valid out pointer
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(FileIdentity {
+        id: FileId {
+            volume: u64::from(info.dwVolumeSerialNumber),
+            index: (u64::from(info.nFileIndexHigh) << 32) | u64::from(info.nFileIndexLow),
+        },
+        links: u64::from(info.nNumberOfLinks),
+    })
+}
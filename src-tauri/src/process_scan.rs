@@ -0,0 +1,311 @@
+use crate::process_store::ProcessRecord;
+use crate::supervisor::SupervisedChild;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind};
+
+// Slack between our recorded spawn time and the OS start time of the process
+const START_TIME_TOLERANCE_SECS: u64 = 5;
+
+// How often an adopted stub is checked for having exited
+const ADOPTED_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// PIDs of running processes named `image_name` whose executable lies under `scope`.
+///
+/// Used when a stub is no longer in the supervisor (e.g. it was started by a
+/// previous session), so only copies living in our own `games/` tree can match.
+pub fn find_owned_processes(scope: &Path, image_name: &str) -> Vec<u32> {
+    processes_under(scope)
+        .into_iter()
+        .filter(|(_, exe)| has_name(exe, image_name))
+        .map(|(pid, _)| pid)
+        .collect()
+}
+
+/// Whether any of `pids` is still running, refreshing only those processes.
+pub fn any_running(pids: &[u32]) -> bool {
+    let pids: Vec<Pid> = pids.iter().copied().map(Pid::from_u32).collect();
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&pids),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    pids.iter().any(|pid| {
+        system
+            .process(*pid)
+            .is_some_and(|process| process.status() != ProcessStatus::Zombie)
+    })
+}
+
+/// Executables of every running process located below `scope`.
+pub fn running_executables_under(scope: &Path) -> Vec<PathBuf> {
+    processes_under(scope)
+        .into_iter()
+        .map(|(_, exe)| exe)
+        .collect()
+}
+
+fn processes_under(scope: &Path) -> Vec<(u32, PathBuf)> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+
+    // The OS reports executable paths with links resolved, so only the scope
+    // needs resolving, not every process on the system
+    let prefixes = scope_prefixes(scope);
+    system
+        .processes()
+        .iter()
+        .filter_map(|(pid, process)| {
+            let exe = process.exe()?;
+            prefixes
+                .iter()
+                .any(|prefix| exe.starts_with(prefix))
+                .then(|| (pid.as_u32(), exe.to_path_buf()))
+        })
+        .collect()
+}
+
+/// `scope` as given and with links resolved.
+fn scope_prefixes(scope: &Path) -> Vec<PathBuf> {
+    let mut prefixes = vec![scope.to_path_buf()];
+    if let Ok(resolved) = std::fs::canonicalize(scope) {
+        prefixes.push(strip_verbatim(resolved));
+    }
+    prefixes
+}
+
+/// Drops the `\\?\` prefix `canonicalize` adds on Windows, which the paths
+/// the OS reports for processes don't have.
+#[cfg(windows)]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    match path.to_str() {
+        Some(text) if text.starts_with(r"\\?\UNC\") => PathBuf::from(format!(r"\\{}", &text[8..])),
+        Some(text) if text.starts_with(r"\\?\") => PathBuf::from(&text[4..]),
+        _ => path,
+    }
+}
+
+#[cfg(not(windows))]
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    path
+}
+
+/// A stub from a previous session, watched through its PID instead of a `Child` handle.
+///
+/// The OS start time is kept with the PID, so a new process that reuses the
+/// PID is not mistaken for the stub.
+pub struct AdoptedProcess {
+    pid: u32,
+    start_time: u64,
+}
+
+impl SupervisedChild for AdoptedProcess {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Polls until the process is gone. Not our child, so there is no exit code.
+    fn wait(self: Box<Self>) -> io::Result<Option<i32>> {
+        let pid = Pid::from_u32(self.pid);
+        let mut system = System::new();
+        loop {
+            system.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&[pid]),
+                true,
+                ProcessRefreshKind::nothing(),
+            );
+            let Some(process) = system.process(pid) else {
+                return Ok(None);
+            };
+            if process.status() == ProcessStatus::Zombie {
+                return Ok(None);
+            }
+            match process.start_time() {
+                // Listed but not readable: whether it is still the stub is unknown
+                0 => {
+                    return Err(io::Error::other(format!(
+                        "cannot read the start time of process {}",
+                        self.pid
+                    )))
+                }
+                start_time if start_time != self.start_time => return Ok(None),
+                _ => std::thread::sleep(ADOPTED_POLL_INTERVAL),
+            }
+        }
+    }
+}
+
+/// Returns a handle to the recorded stub if it is still alive and still ours:
+/// same PID, same executable under `games_root` and a matching start time.
+pub fn find_adoptable(record: &ProcessRecord, games_root: &Path) -> Option<AdoptedProcess> {
+    let pid = Pid::from_u32(record.pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+    );
+
+    let process = system.process(pid)?;
+    let exe = canonical(process.exe()?);
+    let recorded = canonical(&record.executable_path);
+    let owned = exe == recorded
+        && is_owned(&exe, &canonical(games_root), &record.executable_name)
+        && start_time_matches(process.start_time(), record.started_at);
+    owned.then_some(AdoptedProcess {
+        pid: record.pid,
+        start_time: process.start_time(),
+    })
+}
+
+fn start_time_matches(actual: u64, recorded: u64) -> bool {
+    actual.abs_diff(recorded) <= START_TIME_TOLERANCE_SECS
+}
+
+/// True if `exe` is called `image_name` and sits somewhere below `scope`.
+pub fn is_owned(exe: &Path, scope: &Path, image_name: &str) -> bool {
+    has_name(exe, image_name) && exe.starts_with(scope)
+}
+
+fn has_name(exe: &Path, image_name: &str) -> bool {
+    exe.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|file_name| names_match(file_name, image_name))
+}
+
+#[cfg(windows)]
+fn names_match(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+#[cfg(not(windows))]
+fn names_match(a: &str, b: &str) -> bool {
+    a == b
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn games() -> PathBuf {
+        PathBuf::from("/opt/discord-quest/games")
+    }
+
+    #[test]
+    fn matches_stub_inside_games_tree() {
+        let exe = games().join("123").join("bin").join("game.exe");
+        assert!(is_owned(&exe, &games(), "game.exe"));
+        assert!(is_owned(&exe, &games().join("123"), "game.exe"));
+    }
+
+    #[test]
+    fn ignores_same_name_outside_games_tree() {
+        let exe = PathBuf::from("/opt/steam/common/Game/game.exe");
+        assert!(!is_owned(&exe, &games(), "game.exe"));
+
+        let sibling = PathBuf::from("/opt/discord-quest/games-old/123/game.exe");
+        assert!(!is_owned(&sibling, &games(), "game.exe"));
+    }
+
+    #[test]
+    fn start_time_allows_small_drift_only() {
+        assert!(start_time_matches(1_700_000_002, 1_700_000_000));
+        assert!(start_time_matches(1_700_000_000, 1_700_000_004));
+        assert!(!start_time_matches(1_700_000_600, 1_700_000_000));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn adopts_live_stub_from_games_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        let games = dir.path().join("games");
+        let folder = games.join("123");
+        std::fs::create_dir_all(&folder).unwrap();
+        let exe = folder.join("sleep");
+        std::fs::copy("/bin/sleep", &exe).unwrap();
+
+        let mut child = std::process::Command::new(&exe).arg("30").spawn().unwrap();
+        let record = ProcessRecord {
+            key: discord_quest_core::ProcessKey::new("123", "sleep"),
+            app_id: "123".to_string(),
+            executable_name: "sleep".to_string(),
+            game_name: "Sleep".to_string(),
+            pid: child.id(),
+            started_at: crate::supervisor::unix_now(),
+            executable_path: exe.clone(),
+        };
+
+        assert!(find_adoptable(&record, &games).is_some());
+        assert!(find_adoptable(&record, &dir.path().join("elsewhere")).is_none());
+
+        let stale = ProcessRecord {
+            started_at: record.started_at - 3600,
+            ..record.clone()
+        };
+        assert!(find_adoptable(&stale, &games).is_none());
+
+        let adopted = Box::new(find_adoptable(&record, &games).unwrap());
+        let waiter = std::thread::spawn(move || adopted.wait());
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(waiter.join().unwrap().unwrap(), None);
+        assert!(find_adoptable(&record, &games).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reused_pid_counts_as_exited() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let adopted = Box::new(AdoptedProcess {
+            pid: child.id(),
+            start_time: 1,
+        });
+
+        assert_eq!(adopted.wait().unwrap(), None);
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finds_stubs_through_a_linked_games_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("real").join("123");
+        std::fs::create_dir_all(&folder).unwrap();
+        let exe = folder.join("sleep");
+        std::fs::copy("/bin/sleep", &exe).unwrap();
+        let link = dir.path().join("games");
+        std::os::unix::fs::symlink(dir.path().join("real"), &link).unwrap();
+
+        let mut child = std::process::Command::new(&exe).arg("30").spawn().unwrap();
+        let pid = child.id();
+        assert_eq!(find_owned_processes(&link, "sleep"), vec![pid]);
+        assert!(find_owned_processes(&link, "other").is_empty());
+        assert!(running_executables_under(&link).contains(&exe));
+        assert!(any_running(&[pid]));
+
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(!any_running(&[pid]));
+    }
+
+    #[test]
+    fn ignores_other_app_ids_and_names() {
+        let exe = games().join("456").join("game.exe");
+        assert!(!is_owned(&exe, &games().join("123"), "game.exe"));
+        assert!(!is_owned(&exe, &games(), "other.exe"));
+    }
+}
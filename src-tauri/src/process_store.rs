@@ -0,0 +1,150 @@
+use crate::supervisor::{LaunchSpec, SupervisedProcess};
+use discord_quest_core::{ProcessKey, StubArgs};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+
+const STATE_VERSION: u32 = 1;
+
+/// What we remember about a spawned stub so it can be found again after a restart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProcessRecord {
+    pub key: ProcessKey,
+    pub app_id: String,
+    pub executable_name: String,
+    pub game_name: String,
+    pub pid: u32,
+    /// Unix time in seconds.
+    pub started_at: u64,
+    pub executable_path: PathBuf,
+}
+
+impl ProcessRecord {
+    pub fn from_process(process: &SupervisedProcess) -> Self {
+        ProcessRecord {
+            key: process.key.clone(),
+            app_id: process.spec.app_id.clone(),
+            executable_name: process.spec.executable_name.clone(),
+            game_name: process.spec.game_name.clone(),
+            pid: process.pid,
+            started_at: process.started_at,
+            executable_path: process.spec.program.clone(),
+        }
+    }
+
+    /// Launch spec equivalent to the one the stub was started with.
+    pub fn to_spec(&self) -> LaunchSpec {
+        LaunchSpec {
+            app_id: self.app_id.clone(),
+            executable_name: self.executable_name.clone(),
+            game_name: self.game_name.clone(),
+            program: self.executable_path.clone(),
+            args: StubArgs::new(&self.game_name).to_args(),
+            current_dir: self
+                .executable_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StateFile {
+    version: u32,
+    processes: Vec<ProcessRecord>,
+}
+
+/// Small JSON file listing the stubs that were running when it was last written.
+pub struct ProcessStore {
+    path: PathBuf,
+}
+
+impl ProcessStore {
+    pub fn new(path: PathBuf) -> Self {
+        ProcessStore { path }
+    }
+
+    /// A missing, unreadable or newer-version file is treated as empty.
+    pub fn load(&self) -> Vec<ProcessRecord> {
+        let Ok(data) = std::fs::read(&self.path) else {
+            return Vec::new();
+        };
+        match serde_json::from_slice::<StateFile>(&data) {
+            Ok(state) if state.version == STATE_VERSION => state.processes,
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replaces the file contents; written to a temp file first so a crash never leaves half a file.
+    pub fn save(&self, processes: &[ProcessRecord]) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let state = StateFile {
+            version: STATE_VERSION,
+            processes: processes.to_vec(),
+        };
+        let data = serde_json::to_vec_pretty(&state).map_err(io::Error::other)?;
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, data)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(pid: u32) -> ProcessRecord {
+        ProcessRecord {
+            key: ProcessKey::new("123", "game.exe"),
+            app_id: "123".to_string(),
+            executable_name: "game.exe".to_string(),
+            game_name: "Game".to_string(),
+            pid,
+            started_at: 1_700_000_000,
+            executable_path: PathBuf::from("games").join("123").join("game.exe"),
+        }
+    }
+
+    #[test]
+    fn save_then_load_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ProcessStore::new(dir.path().join("state").join("processes.json"));
+
+        store.save(&[record(1), record(2)]).unwrap();
+        assert_eq!(store.load(), vec![record(1), record(2)]);
+
+        store.save(&[]).unwrap();
+        assert!(store.load().is_empty());
+    }
+
+    #[test]
+    fn keys_are_stored_as_strings() {
+        let json = serde_json::to_value(record(1)).unwrap();
+        assert_eq!(json["key"], "123:game.exe");
+    }
+
+    #[test]
+    fn missing_or_corrupt_file_loads_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("processes.json");
+        let store = ProcessStore::new(path.clone());
+        assert!(store.load().is_empty());
+
+        std::fs::write(&path, b"{ not json").unwrap();
+        assert!(store.load().is_empty());
+
+        std::fs::write(&path, br#"{ "version": 99, "processes": [] }"#).unwrap();
+        assert!(store.load().is_empty());
+    }
+
+    #[test]
+    fn record_rebuilds_launch_spec() {
+        let spec = record(1).to_spec();
+        assert_eq!(spec.key(), ProcessKey::new("123", "game.exe"));
+        assert_eq!(spec.current_dir, PathBuf::from("games").join("123"));
+        assert_eq!(StubArgs::parse(spec.args), StubArgs::new("Game"));
+    }
+}
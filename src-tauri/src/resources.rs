@@ -0,0 +1,92 @@
+use discord_quest_core::events::StubResources;
+use discord_quest_core::ProcessKey;
+use std::sync::Mutex;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Samples memory, CPU and handle/thread counts of running stubs.
+///
+/// CPU usage is measured between two refreshes, so the same `System` is kept
+/// across samples instead of building a new one each time.
+pub struct ResourceMonitor {
+    system: Mutex<System>,
+}
+
+impl Default for ResourceMonitor {
+    fn default() -> Self {
+        ResourceMonitor {
+            system: Mutex::new(System::new()),
+        }
+    }
+}
+
+impl ResourceMonitor {
+    /// One entry per process in `processes` that is still alive.
+    pub fn sample(&self, processes: &[(ProcessKey, u32)], now: u64) -> Vec<StubResources> {
+        let pids: Vec<Pid> = processes
+            .iter()
+            .map(|(_, pid)| Pid::from_u32(*pid))
+            .collect();
+
+        let mut system = self.system.lock().unwrap();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing()
+                .with_memory()
+                .with_cpu()
+                .with_tasks(),
+        );
+
+        // sysinfo only lists a process's threads on Linux
+        #[cfg(windows)]
+        let thread_counts = crate::platform::thread_counts().ok();
+
+        processes
+            .iter()
+            .filter_map(|(key, pid)| {
+                let process = system.process(Pid::from_u32(*pid))?;
+                #[cfg(windows)]
+                let threads = thread_counts
+                    .as_ref()
+                    .and_then(|counts| counts.get(pid).copied());
+                #[cfg(not(windows))]
+                let threads = process.tasks().map(|tasks| tasks.len() as u64);
+                Some(StubResources {
+                    app_id: key.app_id.clone(),
+                    executable_name: key.executable_name.clone(),
+                    pid: *pid,
+                    memory_bytes: process.memory(),
+                    virtual_memory_bytes: process.virtual_memory(),
+                    cpu_percent: process.cpu_usage(),
+                    handles: process.open_files().map(|count| count as u64),
+                    threads,
+                    timestamp: now,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_live_processes_and_skips_gone_ones() {
+        let monitor = ResourceMonitor::default();
+        let own = (ProcessKey::new("1", "self"), std::process::id());
+        let gone = (ProcessKey::new("2", "gone"), u32::MAX - 1);
+
+        let samples = monitor.sample(&[own.clone(), gone], 42);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].pid, own.1);
+        assert_eq!(samples[0].app_id, "1");
+        assert_eq!(samples[0].timestamp, 42);
+        assert!(samples[0].memory_bytes > 0);
+        #[cfg(any(target_os = "linux", windows))]
+        assert!(samples[0].threads.unwrap() >= 1);
+
+        // A second sample on the same monitor has a CPU measurement to compare against
+        assert_eq!(monitor.sample(&[own], 43).len(), 1);
+    }
+}
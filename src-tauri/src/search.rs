@@ -0,0 +1,520 @@
+//! Ranked fuzzy search over game names and aliases, built once per catalog load.
+
+use crate::catalog::Game;
+use serde::Serialize;
+use std::cmp::Reverse;
+
+/// Hits per page when the caller doesn't ask for a size.
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Most hits a single page can hold.
+pub const MAX_PAGE_SIZE: usize = 100;
+
+// Tokens at least this long may match a word with one typo
+const TYPO_MIN_LEN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedField {
+    Name,
+    Alias,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchHit {
+    pub game: Game,
+    pub score: u32,
+    pub field: MatchedField,
+    /// The name or alias that matched.
+    pub matched_text: String,
+    /// `[start, end)` offsets into `matched_text` in UTF-16 units, as JS strings index.
+    pub ranges: Vec<[u32; 2]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchPage {
+    /// Hits across all pages.
+    pub total: usize,
+    pub offset: usize,
+    pub hits: Vec<SearchHit>,
+}
+
+/// A name or alias, lowercased and folded for matching, without ©, ® and ™.
+struct Text {
+    chars: Vec<char>,
+    /// UTF-16 span in the original string of each entry in `chars`.
+    spans: Vec<(u32, u32)>,
+    /// Which of `a-z`, `0-9` and "anything else" occur; see [`char_mask`].
+    mask: u64,
+}
+
+impl Text {
+    fn new(original: &str) -> Self {
+        let mut chars = Vec::with_capacity(original.len());
+        let mut spans = Vec::with_capacity(original.len());
+        let mut offset = 0;
+        for c in original.chars() {
+            let end = offset + c.len_utf16() as u32;
+            // Left out so "Minecraft™" is still an exact match for "minecraft"
+            if matches!(c, '©' | '®' | '™') {
+                offset = end;
+                continue;
+            }
+            for folded in c.to_lowercase().map(fold) {
+                chars.push(folded);
+                spans.push((offset, end));
+            }
+            offset = end;
+        }
+        let mask = chars.iter().fold(0, |mask, &c| mask | char_mask(c));
+        Text { chars, spans, mask }
+    }
+
+    fn is_word_start(&self, index: usize) -> bool {
+        index == 0 || !self.chars[index - 1].is_alphanumeric()
+    }
+
+    /// Char ranges of the words, for typo matching.
+    fn words(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut start = None;
+        (0..=self.chars.len()).filter_map(move |i| {
+            let inside = i < self.chars.len() && self.chars[i].is_alphanumeric();
+            match (inside, start) {
+                (true, None) => {
+                    start = Some(i);
+                    None
+                }
+                (false, Some(s)) => {
+                    start = None;
+                    Some((s, i))
+                }
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Strips the accents common in game names so "pokemon" finds "Pokémon".
+fn fold(c: char) -> char {
+    match c {
+        'à'..='å' => 'a',
+        'ç' => 'c',
+        'è'..='ë' => 'e',
+        'ì'..='ï' => 'i',
+        'ñ' => 'n',
+        'ò'..='ö' | 'ø' => 'o',
+        'ù'..='ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        _ => c,
+    }
+}
+
+fn char_mask(c: char) -> u64 {
+    match c {
+        'a'..='z' => 1 << (c as u32 - 'a' as u32),
+        '0'..='9' => 1 << (26 + c as u32 - '0' as u32),
+        _ => 1 << 36,
+    }
+}
+
+struct Entry {
+    game: usize,
+    field: MatchedField,
+    original: String,
+    text: Text,
+}
+
+/// Where a query token, or the whole query, matched inside one text.
+struct TokenMatch {
+    score: u32,
+    /// Char ranges, `[start, end)`.
+    ranges: Vec<(usize, usize)>,
+}
+
+/// The best entry of a game for the current query.
+#[derive(Clone)]
+struct Ranked {
+    score: u32,
+    entry: usize,
+    ranges: Vec<(usize, usize)>,
+}
+
+pub struct SearchIndex {
+    games: Vec<Game>,
+    entries: Vec<Entry>,
+}
+
+impl SearchIndex {
+    pub fn build(games: Vec<Game>) -> Self {
+        let entries = games
+            .iter()
+            .enumerate()
+            .flat_map(|(index, game)| {
+                let name = std::iter::once((MatchedField::Name, &game.name));
+                let aliases = game
+                    .aliases
+                    .iter()
+                    .filter(|alias| !alias.trim().is_empty() && **alias != game.name)
+                    .map(|alias| (MatchedField::Alias, alias));
+                name.chain(aliases).map(move |(field, original)| Entry {
+                    game: index,
+                    field,
+                    original: original.clone(),
+                    text: Text::new(original),
+                })
+            })
+            .collect();
+        SearchIndex { games, entries }
+    }
+
+    /// Best matches first. An empty query lists every game in catalog order.
+    pub fn search(&self, query: &str, offset: usize, limit: usize) -> SearchPage {
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let query = Text::new(query.trim());
+        let tokens: Vec<&[char]> = query
+            .chars
+            .split(|c| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .collect();
+
+        if tokens.is_empty() {
+            let hits = self
+                .games
+                .iter()
+                .skip(offset)
+                .take(limit)
+                .map(|game| SearchHit {
+                    game: game.clone(),
+                    score: 0,
+                    field: MatchedField::Name,
+                    matched_text: game.name.clone(),
+                    ranges: Vec::new(),
+                })
+                .collect();
+            return SearchPage {
+                total: self.games.len(),
+                offset,
+                hits,
+            };
+        }
+
+        // Best entry per game
+        let mut best: Vec<Option<Ranked>> = vec![None; self.games.len()];
+        for (index, entry) in self.entries.iter().enumerate() {
+            let Some(found) = score_entry(&query, &tokens, entry) else {
+                continue;
+            };
+            let slot = &mut best[entry.game];
+            if slot
+                .as_ref()
+                .is_none_or(|current| found.score > current.score)
+            {
+                *slot = Some(Ranked {
+                    score: found.score,
+                    entry: index,
+                    ranges: found.ranges,
+                });
+            }
+        }
+
+        let mut ranked: Vec<Ranked> = best.into_iter().flatten().collect();
+        ranked.sort_by_key(|ranked| {
+            let entry = &self.entries[ranked.entry];
+            (Reverse(ranked.score), entry.text.chars.len(), entry.game)
+        });
+
+        let total = ranked.len();
+        let hits = ranked
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|ranked| {
+                let entry = &self.entries[ranked.entry];
+                SearchHit {
+                    game: self.games[entry.game].clone(),
+                    score: ranked.score,
+                    field: entry.field,
+                    matched_text: entry.original.clone(),
+                    ranges: utf16_ranges(&entry.text, ranked.ranges),
+                }
+            })
+            .collect();
+        SearchPage {
+            total,
+            offset,
+            hits,
+        }
+    }
+}
+
+fn score_entry(query: &Text, tokens: &[&[char]], entry: &Entry) -> Option<TokenMatch> {
+    let text = &entry.text;
+    let mut score = 0;
+    let mut ranges = Vec::new();
+    for token in tokens {
+        let found = match_token(token, text)?;
+        score += found.score;
+        ranges.extend(found.ranges);
+    }
+
+    if text.chars == query.chars {
+        score += 200;
+    } else if text.chars.starts_with(&query.chars) {
+        score += 100;
+    }
+    if entry.field == MatchedField::Alias {
+        score = score.saturating_sub(10);
+    }
+    Some(TokenMatch {
+        score,
+        ranges: merge(ranges),
+    })
+}
+
+fn match_token(token: &[char], text: &Text) -> Option<TokenMatch> {
+    let token_mask = token.iter().fold(0, |mask, &c| mask | char_mask(c));
+    let missing = (token_mask & !text.mask).count_ones();
+    if missing == 0 {
+        if let Some(found) = substring(token, text).or_else(|| subsequence(token, text)) {
+            return Some(found);
+        }
+    }
+    // One edit can bring in at most one char the text doesn't have
+    if missing > 1 {
+        return None;
+    }
+    typo(token, text)
+}
+
+fn substring(token: &[char], text: &Text) -> Option<TokenMatch> {
+    let len = token.len();
+    let mut best: Option<(u32, usize)> = None;
+    for start in 0..=text.chars.len().checked_sub(len)? {
+        if text.chars[start..start + len] != *token {
+            continue;
+        }
+        let bonus = if start == 0 {
+            30
+        } else if text.is_word_start(start) {
+            25
+        } else {
+            15
+        };
+        if best.is_none_or(|(score, _)| bonus > score) {
+            best = Some((bonus, start));
+        }
+    }
+    let (bonus, start) = best?;
+    Some(TokenMatch {
+        score: bonus + 10 * len as u32,
+        ranges: vec![(start, start + len)],
+    })
+}
+
+/// Token chars in order with gaps, e.g. "gta" in "Grand Theft Auto".
+/// Word starts are preferred over the leftmost occurrence.
+fn subsequence(token: &[char], text: &Text) -> Option<TokenMatch> {
+    let mut positions = Vec::with_capacity(token.len());
+    let mut from = 0;
+    for &c in token {
+        let rest = &text.chars[from..];
+        let at_word_start = rest
+            .iter()
+            .enumerate()
+            .position(|(i, &t)| t == c && text.is_word_start(from + i));
+        let next = at_word_start.or_else(|| rest.iter().position(|&t| t == c))?;
+        positions.push(from + next);
+        from += next + 1;
+    }
+
+    let word_starts = positions.iter().filter(|&&p| text.is_word_start(p)).count() as i64;
+    let adjacent = positions.windows(2).filter(|w| w[1] == w[0] + 1).count() as i64;
+    let spread = (positions[positions.len() - 1] - positions[0] + 1 - positions.len()) as i64;
+    let score = 4 * token.len() as i64 + 6 * word_starts + 3 * adjacent
+        - spread.min(3 * token.len() as i64);
+    if score <= 0 || (word_starts == 0 && token.len() > 1 && adjacent == 0) {
+        return None;
+    }
+    Some(TokenMatch {
+        score: score as u32,
+        ranges: positions.iter().map(|&p| (p, p + 1)).collect(),
+    })
+}
+
+/// A word (or the start of one) at most one edit away from the token.
+fn typo(token: &[char], text: &Text) -> Option<TokenMatch> {
+    if token.len() < TYPO_MIN_LEN {
+        return None;
+    }
+    text.words().find_map(|(start, end)| {
+        let word = &text.chars[start..end];
+        // Also try prefixes so "witchr" still finds "Witcher 3"
+        (token.len() - 1..=token.len() + 1)
+            .filter(|&len| len <= word.len())
+            .find(|&len| within_one_edit(token, &word[..len]))
+            .map(|len| TokenMatch {
+                score: 10 + 6 * token.len() as u32,
+                ranges: vec![(start, start + len)],
+            })
+    })
+}
+
+fn within_one_edit(a: &[char], b: &[char]) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    let prefix = short.iter().zip(long).take_while(|(x, y)| x == y).count();
+    if prefix == short.len() {
+        return true;
+    }
+    if short.len() == long.len() {
+        // One substitution, or two swapped neighbours
+        short[prefix + 1..] == long[prefix + 1..]
+            || (prefix + 1 < short.len()
+                && short[prefix] == long[prefix + 1]
+                && short[prefix + 1] == long[prefix]
+                && short[prefix + 2..] == long[prefix + 2..])
+    } else {
+        short[prefix..] == long[prefix + 1..]
+    }
+}
+
+fn merge(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+fn utf16_ranges(text: &Text, ranges: Vec<(usize, usize)>) -> Vec<[u32; 2]> {
+    let mut result: Vec<[u32; 2]> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        let range = [text.spans[start].0, text.spans[end - 1].1];
+        // Folding can turn one char into several; keep the output non-overlapping
+        match result.last_mut() {
+            Some(last) if range[0] <= last[1] => last[1] = last[1].max(range[1]),
+            _ => result.push(range),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(id: &str, name: &str, aliases: &[&str]) -> Game {
+        Game {
+            id: id.to_string(),
+            name: name.to_string(),
+            executables: Vec::new(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            themes: Vec::new(),
+        }
+    }
+
+    fn index() -> SearchIndex {
+        SearchIndex::build(vec![
+            game("1", "Grand Theft Auto V", &["GTA V", "GTA 5"]),
+            game("2", "Minecraft", &["Minecraft Java Edition"]),
+            game("3", "Minecraft Dungeons", &[]),
+            game("4", "Pokémon Legends: Arceus", &[]),
+            game("5", "The Witcher 3: Wild Hunt", &[]),
+            game("6", "Mine Blocks", &[]),
+        ])
+    }
+
+    fn ids(page: &SearchPage) -> Vec<&str> {
+        page.hits.iter().map(|hit| hit.game.id.as_str()).collect()
+    }
+
+    #[test]
+    fn exact_beats_prefix_beats_loose_matches() {
+        let page = index().search("minecraft", 0, 10);
+        assert_eq!(ids(&page), ["2", "3"]);
+        assert_eq!(page.hits[0].ranges, [[0, 9]]);
+
+        let page = index().search("mine", 0, 10);
+        assert_eq!(ids(&page), ["2", "6", "3"]);
+    }
+
+    #[test]
+    fn aliases_are_searched() {
+        let page = index().search("gta 5", 0, 10);
+        assert_eq!(ids(&page), ["1"]);
+        assert_eq!(page.hits[0].field, MatchedField::Alias);
+        assert_eq!(page.hits[0].matched_text, "GTA 5");
+        assert_eq!(page.hits[0].ranges, [[0, 3], [4, 5]]);
+    }
+
+    #[test]
+    fn initials_and_typos_still_match() {
+        assert_eq!(ids(&index().search("gtav", 0, 10)), ["1"]);
+        assert_eq!(ids(&index().search("witchr", 0, 10)), ["5"]);
+        assert_eq!(ids(&index().search("wticher", 0, 10)), ["5"]);
+        assert!(index().search("zelda", 0, 10).hits.is_empty());
+    }
+
+    #[test]
+    fn accents_and_symbols_fold_and_ranges_are_utf16() {
+        let index = SearchIndex::build(vec![game("1", "🎮 Pokémon", &[])]);
+        let page = index.search("pokemon", 0, 10);
+        assert_eq!(ids(&page), ["1"]);
+        // The emoji takes two UTF-16 units, the space one
+        assert_eq!(page.hits[0].ranges, [[3, 10]]);
+
+        let index = SearchIndex::build(vec![
+            game("1", "Minecraft™ Legends", &[]),
+            game("2", "Minecraft®", &[]),
+        ]);
+        let page = index.search("minecraft", 0, 10);
+        assert_eq!(ids(&page), ["2", "1"]);
+        assert_eq!(page.hits[0].ranges, [[0, 9]]);
+    }
+
+    #[test]
+    fn every_token_must_match() {
+        assert_eq!(ids(&index().search("wild witcher", 0, 10)), ["5"]);
+        assert!(index().search("wild minecraft", 0, 10).hits.is_empty());
+    }
+
+    #[test]
+    fn pages_through_results() {
+        let index = index();
+        let page = index.search("m", 0, 2);
+        let all = index.search("m", 0, 100);
+        assert_eq!(page.total, all.total);
+        assert_eq!(page.hits, all.hits[..2]);
+        assert_eq!(index.search("m", 2, 2).hits, all.hits[2..4]);
+        assert!(index.search("m", 100, 10).hits.is_empty());
+    }
+
+    #[test]
+    fn empty_query_lists_the_catalog() {
+        let page = index().search("  ", 1, 2);
+        assert_eq!(page.total, 6);
+        assert_eq!(ids(&page), ["2", "3"]);
+    }
+
+    #[test]
+    fn one_edit() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        for (a, b) in [
+            ("witcher", "witchr"),
+            ("witcher", "wticher"),
+            ("abcd", "abxd"),
+            ("abcd", "abcd"),
+        ] {
+            assert!(within_one_edit(&chars(a), &chars(b)), "{a} {b}");
+        }
+        for (a, b) in [("abcd", "badc"), ("abcd", "ab"), ("abcd", "xbcx")] {
+            assert!(!within_one_edit(&chars(a), &chars(b)), "{a} {b}");
+        }
+    }
+}
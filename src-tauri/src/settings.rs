@@ -0,0 +1,368 @@
+use crate::games_dir::default_games_root;
+use discord_quest_core::game_path::STORE_DIR;
+use discord_quest_core::StubArgs;
+use discord_quest_i18n::{self as i18n, Lang};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const SETTINGS_VERSION: u32 = 2;
+
+/// Allowed range for `stop_grace_ms`.
+pub const STOP_GRACE_RANGE_MS: (u64, u64) = (100, 60_000);
+
+// How long a stub gets to close after WM_CLOSE / SIGTERM before it is force-killed
+const DEFAULT_STOP_GRACE_MS: u64 = 1500;
+
+/// What happens to running stubs when the app quits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitBehavior {
+    #[default]
+    StopStubs,
+    /// Leave them running; they are re-adopted on the next start.
+    KeepStubs,
+}
+
+/// User preferences for the backend, stored as `settings.json` in the config directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    /// Where fake games are created; `None` keeps them next to the app executable.
+    pub games_dir: Option<PathBuf>,
+    /// Language of backend messages and of the stub windows.
+    pub language: Lang,
+    /// Time a stub gets to close on its own before it is force-killed.
+    pub stop_grace_ms: u64,
+    /// Start stubs with only their tray icon, without showing the window.
+    pub start_minimized: bool,
+    /// Most stubs allowed to run at once; 0 means no limit.
+    pub max_concurrent_stubs: u32,
+    /// Minutes after which a stub closes on its own; 0 means never.
+    pub max_runtime_mins: u32,
+    pub exit_behavior: ExitBehavior,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            games_dir: None,
+            language: Lang::default(),
+            stop_grace_ms: DEFAULT_STOP_GRACE_MS,
+            start_minimized: false,
+            max_concurrent_stubs: 0,
+            max_runtime_mins: 0,
+            exit_behavior: ExitBehavior::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// The games root in use. A relative `games_dir` (e.g. from a hand-edited
+    /// file) is ignored rather than resolved against the working directory.
+    pub fn games_root(&self) -> PathBuf {
+        match &self.games_dir {
+            Some(dir) if dir.is_absolute() => dir.clone(),
+            _ => default_games_root(),
+        }
+    }
+
+    pub fn stop_grace(&self) -> Duration {
+        let (min, max) = STOP_GRACE_RANGE_MS;
+        Duration::from_millis(self.stop_grace_ms.clamp(min, max))
+    }
+
+    /// Command line for a stub showing `title`, launched by this app.
+    pub fn stub_args(&self, title: &str) -> StubArgs {
+        StubArgs {
+            lang: self.language,
+            // Stubs meant to outlive the app must not follow it when it exits
+            parent_pid: (self.exit_behavior == ExitBehavior::StopStubs).then(std::process::id),
+            max_runtime: (self.max_runtime_mins > 0)
+                .then(|| Duration::from_secs(u64::from(self.max_runtime_mins) * 60)),
+            start_minimized: self.start_minimized,
+            ..StubArgs::new(title)
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(dir) = &self.games_dir {
+            if !dir.is_absolute() {
+                return Err(SettingsError::GamesDirNotAbsolute(
+                    dir.display().to_string(),
+                ));
+            }
+            if !is_usable_games_dir(dir) {
+                return Err(SettingsError::GamesDirInUse(dir.display().to_string()));
+            }
+        }
+        let (min, max) = STOP_GRACE_RANGE_MS;
+        if !(min..=max).contains(&self.stop_grace_ms) {
+            return Err(SettingsError::StopGraceOutOfRange(self.stop_grace_ms));
+        }
+        Ok(())
+    }
+}
+
+/// Cleanup deletes files in the games folder, so only folders that hold
+/// nothing else are accepted: missing, empty, or already holding our stub store.
+fn is_usable_games_dir(dir: &Path) -> bool {
+    match std::fs::read_dir(dir) {
+        Ok(mut items) => items.next().is_none() || dir.join(STORE_DIR).is_dir(),
+        Err(e) => e.kind() == io::ErrorKind::NotFound,
+    }
+}
+
+/// Creates the stub store in the configured games folder so the folder keeps
+/// passing [`Settings::validate`] once other files are added to it.
+pub fn claim_games_dir(settings: &Settings) -> io::Result<()> {
+    match &settings.games_dir {
+        Some(dir) => std::fs::create_dir_all(dir.join(STORE_DIR)),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsError {
+    GamesDirNotAbsolute(String),
+    /// The folder exists and holds files that are not ours.
+    GamesDirInUse(String),
+    StopGraceOutOfRange(u64),
+}
+
+impl SettingsError {
+    pub fn message(&self, lang: Lang) -> String {
+        match self {
+            SettingsError::GamesDirNotAbsolute(dir) => {
+                i18n::format(lang, "settings.games_dir_not_absolute", &[dir])
+            }
+            SettingsError::GamesDirInUse(dir) => {
+                i18n::format(lang, "settings.games_dir_in_use", &[dir])
+            }
+            SettingsError::StopGraceOutOfRange(value) => {
+                let (min, max) = STOP_GRACE_RANGE_MS;
+                i18n::format(
+                    lang,
+                    "settings.stop_grace_out_of_range",
+                    &[value, &min, &max],
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(Lang::default()))
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// Upgrades the contents of a settings file by one version.
+type Migration = fn(&mut Map<String, Value>);
+
+// `MIGRATIONS[n]` upgrades version `n + 1`
+const MIGRATIONS: [Migration; 1] = [migrate_v1];
+
+fn migrate_v1(settings: &mut Map<String, Value>) {
+    let keep = settings.remove("keep_stubs_on_exit") == Some(Value::Bool(true));
+    let behavior = if keep {
+        ExitBehavior::KeepStubs
+    } else {
+        ExitBehavior::StopStubs
+    };
+    settings.insert(
+        "exit_behavior".to_string(),
+        serde_json::to_value(behavior).unwrap(),
+    );
+}
+
+/// Parses a settings file of any version. Returns the settings and whether
+/// they were migrated from an older version.
+fn parse(data: &[u8]) -> Option<(Settings, bool)> {
+    let Value::Object(mut map) = serde_json::from_slice(data).ok()? else {
+        return None;
+    };
+    // Files without a version are read as the first one
+    let version = map.get("version").and_then(Value::as_u64).unwrap_or(1) as u32;
+
+    let mut migrated = false;
+    for migration in MIGRATIONS.iter().skip(version.saturating_sub(1) as usize) {
+        migration(&mut map);
+        migrated = true;
+    }
+    // A newer app may have written fields this one does not know; they are dropped
+    map.insert("version".to_string(), SETTINGS_VERSION.into());
+
+    let settings = serde_json::from_value(Value::Object(map)).ok()?;
+    Some((settings, migrated))
+}
+
+pub struct SettingsStore {
+    path: PathBuf,
+    current: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    /// Reads the file at `path`; a missing or unreadable file gives the defaults.
+    /// Files from older versions are migrated and written back.
+    pub fn load(path: PathBuf) -> Self {
+        let (current, migrated) = std::fs::read(&path)
+            .ok()
+            .and_then(|data| parse(&data))
+            .unwrap_or_default();
+        if migrated {
+            let _ = write(&path, &current);
+        }
+        SettingsStore {
+            path,
+            current: Mutex::new(current),
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Persists `settings` and makes them current.
+    pub fn update(&self, mut settings: Settings) -> io::Result<Settings> {
+        settings.version = SETTINGS_VERSION;
+        write(&self.path, &settings)?;
+        *self.current.lock().unwrap() = settings.clone();
+        Ok(settings)
+    }
+}
+
+fn write(path: &Path, settings: &Settings) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_vec_pretty(settings).map_err(io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_file_gives_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SettingsStore::load(dir.path().join("settings.json"));
+        assert_eq!(store.get(), Settings::default());
+    }
+
+    #[test]
+    fn update_persists_across_loads() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config").join("settings.json");
+
+        let store = SettingsStore::load(path.clone());
+        store
+            .update(Settings {
+                exit_behavior: ExitBehavior::KeepStubs,
+                stop_grace_ms: 3000,
+                ..Settings::default()
+            })
+            .unwrap();
+
+        let loaded = SettingsStore::load(path).get();
+        assert_eq!(loaded.exit_behavior, ExitBehavior::KeepStubs);
+        assert_eq!(loaded.stop_grace(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn version_1_files_are_migrated_and_rewritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, r#"{"version": 1, "keep_stubs_on_exit": true}"#).unwrap();
+
+        let settings = SettingsStore::load(path.clone()).get();
+        assert_eq!(settings.exit_behavior, ExitBehavior::KeepStubs);
+        assert_eq!(settings.language, Lang::Es);
+        assert_eq!(settings.stop_grace_ms, DEFAULT_STOP_GRACE_MS);
+
+        let on_disk: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(on_disk["version"], SETTINGS_VERSION);
+        assert_eq!(on_disk["exit_behavior"], "keep_stubs");
+        assert!(on_disk.get("keep_stubs_on_exit").is_none());
+    }
+
+    #[test]
+    fn current_files_are_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let contents = r#"{"version": 2, "language": "en"}"#;
+        std::fs::write(&path, contents).unwrap();
+
+        assert_eq!(SettingsStore::load(path.clone()).get().language, Lang::En);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn stub_args_carry_the_runtime_limit() {
+        let args = Settings::default().stub_args("Game").to_args();
+        assert!(!args.contains(&"--max-runtime".to_string()));
+
+        let limited = Settings {
+            max_runtime_mins: 90,
+            ..Settings::default()
+        };
+        let args = limited.stub_args("Game").to_args();
+        let flag = args.iter().position(|arg| arg == "--max-runtime").unwrap();
+        assert_eq!(args[flag + 1], "5400");
+        assert_eq!(
+            StubArgs::parse(args).max_runtime,
+            Some(Duration::from_secs(5400))
+        );
+    }
+
+    #[test]
+    fn validation() {
+        assert_eq!(Settings::default().validate(), Ok(()));
+
+        let relative = Settings {
+            games_dir: Some(PathBuf::from("games")),
+            ..Settings::default()
+        };
+        assert!(matches!(
+            relative.validate(),
+            Err(SettingsError::GamesDirNotAbsolute(_))
+        ));
+        assert_eq!(relative.games_root(), default_games_root());
+
+        let dir = tempfile::tempdir().unwrap();
+        let games = |path: &Path| Settings {
+            games_dir: Some(path.to_path_buf()),
+            ..Settings::default()
+        };
+        assert_eq!(games(&dir.path().join("new")).validate(), Ok(()));
+        assert_eq!(games(dir.path()).validate(), Ok(()));
+        std::fs::write(dir.path().join("notes.txt"), b"mine").unwrap();
+        assert!(matches!(
+            games(dir.path()).validate(),
+            Err(SettingsError::GamesDirInUse(_))
+        ));
+        claim_games_dir(&games(dir.path())).unwrap();
+        assert_eq!(games(dir.path()).validate(), Ok(()));
+
+        let too_short = Settings {
+            stop_grace_ms: 10,
+            ..Settings::default()
+        };
+        assert_eq!(
+            too_short.validate(),
+            Err(SettingsError::StopGraceOutOfRange(10))
+        );
+        assert_eq!(too_short.stop_grace(), Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,99 @@
+use discord_quest_ipc::{Client, Event, Request, Response, StubStatus, RESPONSE_TIMEOUT};
+use std::io;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// A freshly spawned stub needs a moment before its listener is up.
+const WATCH_CONNECT_WAIT: Duration = Duration::from_secs(5);
+
+/// Requests target stubs that are already running: one connection attempt, no retries.
+///
+/// Named pipes can't time out a read, so the exchange runs on its own thread
+/// and is given up after `RESPONSE_TIMEOUT` on every platform. A stuck stub
+/// leaves that thread blocked until the stub exits.
+fn request(pid: u32, request: Request) -> io::Result<Response> {
+    with_timeout(RESPONSE_TIMEOUT, move || {
+        Client::connect(pid, Duration::ZERO)?.request(request)
+    })
+}
+
+fn with_timeout<T: Send + 'static>(
+    timeout: Duration,
+    f: impl FnOnce() -> io::Result<T> + Send + 'static,
+) -> io::Result<T> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(f());
+    });
+    receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "the stub did not answer in time",
+        ))
+    })
+}
+
+fn unexpected(response: Response) -> io::Error {
+    match response {
+        Response::Error { message } => io::Error::other(message),
+        other => io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unexpected response: {:?}", other),
+        ),
+    }
+}
+
+fn expect_done(response: Response) -> io::Result<()> {
+    match response {
+        Response::Done => Ok(()),
+        other => Err(unexpected(other)),
+    }
+}
+
+pub fn status(pid: u32) -> io::Result<StubStatus> {
+    match request(pid, Request::GetStatus)? {
+        Response::Status(status) => Ok(status),
+        other => Err(unexpected(other)),
+    }
+}
+
+/// Asks the stub to close its window, the same as the user doing it.
+pub fn close(pid: u32) -> io::Result<()> {
+    expect_done(request(pid, Request::Close)?)
+}
+
+pub fn show_window(pid: u32) -> io::Result<()> {
+    expect_done(request(pid, Request::ShowWindow)?)
+}
+
+/// Calls `on_event` from a background thread for every event the stub sends,
+/// until it exits. Stubs without the channel (older builds) are ignored.
+pub fn watch(pid: u32, mut on_event: impl FnMut(Event) + Send + 'static) {
+    std::thread::spawn(move || {
+        let Ok(mut client) = Client::connect(pid, WATCH_CONNECT_WAIT) else {
+            return;
+        };
+        while let Ok(event) = client.next_event() {
+            on_event(event);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn stuck_requests_are_given_up() {
+        let started = Instant::now();
+        let result: io::Result<()> = with_timeout(Duration::from_millis(50), || {
+            std::thread::sleep(Duration::from_secs(5));
+            Ok(())
+        });
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        assert_eq!(with_timeout(Duration::from_secs(1), || Ok(7)).unwrap(), 7);
+    }
+}
@@ -0,0 +1,931 @@
+use crate::error::CommandError;
+use crate::platform::backend;
+use crate::stub_channel;
+use discord_quest_core::events::StopOutcome;
+use discord_quest_core::ProcessKey;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Everything needed to launch one stub and to describe it in events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchSpec {
+    pub app_id: String,
+    pub executable_name: String,
+    pub game_name: String,
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub current_dir: PathBuf,
+}
+
+impl LaunchSpec {
+    pub fn key(&self) -> ProcessKey {
+        ProcessKey::new(&self.app_id, &self.executable_name)
+    }
+}
+
+/// A launched process as seen by the supervisor.
+pub trait SupervisedChild: Send {
+    fn pid(&self) -> u32;
+
+    /// Blocks until the process exits and returns its exit code, if any.
+    fn wait(self: Box<Self>) -> io::Result<Option<i32>>;
+}
+
+/// Starts and signals processes. Injected so tests can run without real executables.
+pub trait Spawner: Send + Sync {
+    fn spawn(&self, spec: &LaunchSpec) -> io::Result<Box<dyn SupervisedChild>>;
+
+    fn terminate(&self, pid: u32) -> io::Result<()>;
+
+    fn force_kill(&self, pid: u32) -> io::Result<()>;
+}
+
+pub struct NativeSpawner;
+
+impl SupervisedChild for std::process::Child {
+    fn pid(&self) -> u32 {
+        self.id()
+    }
+
+    fn wait(mut self: Box<Self>) -> io::Result<Option<i32>> {
+        std::process::Child::wait(&mut self).map(|status| status.code())
+    }
+}
+
+impl Spawner for NativeSpawner {
+    fn spawn(&self, spec: &LaunchSpec) -> io::Result<Box<dyn SupervisedChild>> {
+        let mut command = Command::new(&spec.program);
+        command.args(&spec.args).current_dir(&spec.current_dir);
+        let child = backend().spawn(&mut command)?;
+        Ok(Box::new(child))
+    }
+
+    /// Asks the stub over its control channel, falling back to the OS for
+    /// stubs that can't be reached or don't answer in time.
+    fn terminate(&self, pid: u32) -> io::Result<()> {
+        stub_channel::close(pid).or_else(|_| backend().terminate(pid))
+    }
+
+    fn force_kill(&self, pid: u32) -> io::Result<()> {
+        backend().force_kill(pid)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessState {
+    Running,
+    Stopping,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupervisedProcess {
+    pub key: ProcessKey,
+    pub pid: u32,
+    pub state: ProcessState,
+    pub spec: LaunchSpec,
+    /// Unix time in seconds.
+    pub started_at: u64,
+}
+
+impl SupervisedProcess {
+    /// Seconds since the start; 0 if the clock went back since.
+    pub fn uptime_secs(&self, now: u64) -> u64 {
+        now.saturating_sub(self.started_at)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SupervisorEvent {
+    /// Also sent for adopted processes, with their original start time.
+    Started {
+        spec: LaunchSpec,
+        pid: u32,
+        started_at: u64,
+    },
+    StopRequested {
+        spec: LaunchSpec,
+        pid: u32,
+        started_at: u64,
+    },
+    /// The grace period ran out and the process was killed; `Exited` follows.
+    ForceKilled {
+        spec: LaunchSpec,
+        pid: u32,
+        started_at: u64,
+    },
+    /// A `stop`/`stop_wait` call is done with this process.
+    StopFinished {
+        spec: LaunchSpec,
+        pid: u32,
+        started_at: u64,
+        outcome: StopOutcome,
+    },
+    Exited {
+        spec: LaunchSpec,
+        pid: u32,
+        started_at: u64,
+        exit_code: Option<i32>,
+        /// The exit followed a `stop`/`stop_all` call.
+        stop_requested: bool,
+    },
+    WaitFailed {
+        spec: LaunchSpec,
+        pid: u32,
+        started_at: u64,
+        error: String,
+        stop_requested: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopRequest {
+    /// Termination was requested; the process is force-killed if it outlives the grace period.
+    Stopping(u32),
+    /// A previous stop for this key is still in progress.
+    AlreadyStopping(u32),
+    /// No supervised process has this key.
+    Unknown,
+}
+
+/// A process marked `Stopping`, plus a way to learn when it exits.
+struct PendingStop {
+    spec: LaunchSpec,
+    pid: u32,
+    started_at: u64,
+    exited: Receiver<()>,
+}
+
+enum StopStart {
+    Unknown,
+    AlreadyStopping(PendingStop),
+    Begun(PendingStop),
+}
+
+struct Entry {
+    spec: LaunchSpec,
+    pid: u32,
+    started_at: u64,
+    state: ProcessState,
+    exit_watchers: Vec<Sender<()>>,
+}
+
+/// Owns every spawned stub.
+///
+/// Each child gets a waiter thread blocked in `wait()`, so exits are reported
+/// as soon as they happen and the shared map is only locked on state changes.
+/// Lifecycle changes are published as [`SupervisorEvent`]s on the channel
+/// returned by [`ProcessSupervisor::new`].
+#[derive(Clone)]
+pub struct ProcessSupervisor {
+    spawner: Arc<dyn Spawner>,
+    entries: Arc<Mutex<HashMap<ProcessKey, Entry>>>,
+    events: EventSender,
+}
+
+/// The sending side of the event channel, counting what it has published.
+#[derive(Clone)]
+struct EventSender {
+    sender: Sender<SupervisorEvent>,
+    sent: Arc<AtomicU64>,
+}
+
+impl EventSender {
+    /// Events sent after the receiver is gone are dropped.
+    fn send(&self, event: SupervisorEvent) {
+        if self.sender.send(event).is_ok() {
+            self.sent.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl ProcessSupervisor {
+    pub fn new(spawner: Arc<dyn Spawner>) -> (Self, Receiver<SupervisorEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        let supervisor = ProcessSupervisor {
+            spawner,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            events: EventSender {
+                sender,
+                sent: Arc::new(AtomicU64::new(0)),
+            },
+        };
+        (supervisor, receiver)
+    }
+
+    /// How many events have been published so far. A consumer that has handled
+    /// this many has seen everything that happened before the call, including
+    /// the exits of processes [`stop_all`](Self::stop_all) waited for.
+    pub fn events_sent(&self) -> u64 {
+        self.events.sent.load(Ordering::SeqCst)
+    }
+
+    /// Starts `spec` unless a process with the same key is still running or
+    /// stopping, in which case it fails with [`CommandError::AlreadyRunning`].
+    pub fn spawn(&self, spec: LaunchSpec) -> Result<u32, CommandError> {
+        self.spawn_within(spec, 0)
+    }
+
+    /// Like [`spawn`](Self::spawn), but fails with [`CommandError::LimitReached`]
+    /// when `limit` processes (0 means no limit) are already supervised.
+    pub fn spawn_within(&self, spec: LaunchSpec, limit: u32) -> Result<u32, CommandError> {
+        // Held across the spawn so two launches can't both pass the checks
+        let mut entries = self.entries.lock().unwrap();
+        refuse_live_key(&entries, &spec)?;
+        if limit != 0 && entries.len() >= limit as usize {
+            return Err(CommandError::LimitReached(limit));
+        }
+        let child = self
+            .spawner
+            .spawn(&spec)
+            .map_err(CommandError::SpawnFailed)?;
+        Ok(self.track(&mut entries, spec, child, unix_now()))
+    }
+
+    /// Takes over monitoring of a stub this supervisor did not spawn, e.g. one
+    /// left running by a previous session. Refused like [`spawn`](Self::spawn)
+    /// if the key is already supervised.
+    pub fn adopt(
+        &self,
+        spec: LaunchSpec,
+        child: Box<dyn SupervisedChild>,
+        started_at: u64,
+    ) -> Result<u32, CommandError> {
+        let mut entries = self.entries.lock().unwrap();
+        refuse_live_key(&entries, &spec)?;
+        Ok(self.track(&mut entries, spec, child, started_at))
+    }
+
+    fn track(
+        &self,
+        entries: &mut HashMap<ProcessKey, Entry>,
+        spec: LaunchSpec,
+        child: Box<dyn SupervisedChild>,
+        started_at: u64,
+    ) -> u32 {
+        let pid = child.pid();
+        let key = spec.key();
+
+        entries.insert(
+            key.clone(),
+            Entry {
+                spec: spec.clone(),
+                pid,
+                started_at,
+                state: ProcessState::Running,
+                exit_watchers: Vec::new(),
+            },
+        );
+        self.events.send(SupervisorEvent::Started {
+            spec: spec.clone(),
+            pid,
+            started_at,
+        });
+
+        let entries = Arc::clone(&self.entries);
+        let events = self.events.clone();
+        std::thread::spawn(move || {
+            let result = child.wait();
+
+            let mut stop_requested = false;
+            let mut exit_watchers = Vec::new();
+            {
+                let mut entries = entries.lock().unwrap();
+                // The key may have been reused by a newer launch; only remove our own entry
+                if entries.get(&key).is_some_and(|entry| entry.pid == pid) {
+                    if let Some(entry) = entries.remove(&key) {
+                        stop_requested = entry.state == ProcessState::Stopping;
+                        exit_watchers = entry.exit_watchers;
+                    }
+                }
+            }
+
+            let event = match result {
+                Ok(exit_code) => SupervisorEvent::Exited {
+                    spec,
+                    pid,
+                    started_at,
+                    exit_code,
+                    stop_requested,
+                },
+                Err(e) => SupervisorEvent::WaitFailed {
+                    spec,
+                    pid,
+                    started_at,
+                    error: e.to_string(),
+                    stop_requested,
+                },
+            };
+            // Published before waking the watchers, so whoever waited for the exit
+            // can rely on the event being queued
+            events.send(event);
+            for watcher in exit_watchers {
+                let _ = watcher.send(());
+            }
+        });
+
+        pid
+    }
+
+    /// Asks the process to close and force-kills it if it is still alive after `grace`.
+    /// Does not wait; the close and any force-kill happen on a background thread.
+    pub fn stop(&self, key: &ProcessKey, grace: Duration) -> StopRequest {
+        match self.begin_stop(key) {
+            StopStart::Unknown => StopRequest::Unknown,
+            StopStart::AlreadyStopping(pending) => StopRequest::AlreadyStopping(pending.pid),
+            StopStart::Begun(pending) => {
+                let pid = pending.pid;
+                let supervisor = self.clone();
+                std::thread::spawn(move || supervisor.finish_stop(pending, grace, grace));
+                StopRequest::Stopping(pid)
+            }
+        }
+    }
+
+    /// Like [`stop`](Self::stop), but blocks until the process is gone or
+    /// `kill_wait` has passed after the force-kill. `None` if no supervised
+    /// process has this key.
+    ///
+    /// If another stop is already in progress this waits for it instead and
+    /// reports `AlreadyExited` once the process is gone.
+    pub fn stop_wait(
+        &self,
+        key: &ProcessKey,
+        grace: Duration,
+        kill_wait: Duration,
+    ) -> Option<StopOutcome> {
+        match self.begin_stop(key) {
+            StopStart::Unknown => None,
+            StopStart::AlreadyStopping(pending) => {
+                Some(if pending.exited.recv_timeout(grace + kill_wait).is_ok() {
+                    StopOutcome::AlreadyExited
+                } else {
+                    StopOutcome::Failed
+                })
+            }
+            StopStart::Begun(pending) => Some(self.finish_stop(pending, grace, kill_wait)),
+        }
+    }
+
+    fn begin_stop(&self, key: &ProcessKey) -> StopStart {
+        let mut entries = self.entries.lock().unwrap();
+        let Some(entry) = entries.get_mut(key) else {
+            return StopStart::Unknown;
+        };
+        let (watcher, exited) = mpsc::channel();
+        entry.exit_watchers.push(watcher);
+        let pending = PendingStop {
+            spec: entry.spec.clone(),
+            pid: entry.pid,
+            started_at: entry.started_at,
+            exited,
+        };
+        if entry.state == ProcessState::Stopping {
+            return StopStart::AlreadyStopping(pending);
+        }
+        entry.state = ProcessState::Stopping;
+        StopStart::Begun(pending)
+    }
+
+    fn finish_stop(&self, stop: PendingStop, grace: Duration, kill_wait: Duration) -> StopOutcome {
+        let PendingStop {
+            spec,
+            pid,
+            started_at,
+            exited,
+        } = stop;
+
+        self.events.send(SupervisorEvent::StopRequested {
+            spec: spec.clone(),
+            pid,
+            started_at,
+        });
+        let _ = self.spawner.terminate(pid);
+
+        let outcome = if exited.recv_timeout(grace).is_ok() {
+            StopOutcome::Graceful
+        } else {
+            let _ = self.spawner.force_kill(pid);
+            self.events.send(SupervisorEvent::ForceKilled {
+                spec: spec.clone(),
+                pid,
+                started_at,
+            });
+            if exited.recv_timeout(kill_wait).is_ok() {
+                StopOutcome::Forced
+            } else {
+                StopOutcome::Failed
+            }
+        };
+
+        self.events.send(SupervisorEvent::StopFinished {
+            spec,
+            pid,
+            started_at,
+            outcome,
+        });
+        outcome
+    }
+
+    /// Stops every supervised process and blocks until they are gone.
+    ///
+    /// Processes still alive after `grace` are force-killed; returns how many
+    /// had not exited `kill_wait` after that.
+    pub fn stop_all(&self, grace: Duration, kill_wait: Duration) -> usize {
+        let pending: Vec<(LaunchSpec, u32, u64, bool, Receiver<()>)> = {
+            let mut entries = self.entries.lock().unwrap();
+            entries
+                .values_mut()
+                .map(|entry| {
+                    let was_running = entry.state == ProcessState::Running;
+                    entry.state = ProcessState::Stopping;
+                    let (watcher, exited) = mpsc::channel();
+                    entry.exit_watchers.push(watcher);
+                    (
+                        entry.spec.clone(),
+                        entry.pid,
+                        entry.started_at,
+                        was_running,
+                        exited,
+                    )
+                })
+                .collect()
+        };
+
+        for (spec, pid, started_at, was_running, _) in &pending {
+            if *was_running {
+                self.events.send(SupervisorEvent::StopRequested {
+                    spec: spec.clone(),
+                    pid: *pid,
+                    started_at: *started_at,
+                });
+                let _ = self.spawner.terminate(*pid);
+            }
+        }
+
+        let deadline = Instant::now() + grace;
+        let mut killed = Vec::new();
+        for (spec, pid, started_at, _, exited) in pending {
+            if exited
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .is_err()
+            {
+                let _ = self.spawner.force_kill(pid);
+                self.events.send(SupervisorEvent::ForceKilled {
+                    spec,
+                    pid,
+                    started_at,
+                });
+                killed.push(exited);
+            }
+        }
+
+        let deadline = Instant::now() + kill_wait;
+        killed
+            .into_iter()
+            .filter(|exited| {
+                exited
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    .is_err()
+            })
+            .count()
+    }
+
+    pub fn snapshot(&self) -> Vec<SupervisedProcess> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .map(|(key, entry)| SupervisedProcess {
+                key: key.clone(),
+                pid: entry.pid,
+                state: entry.state,
+                spec: entry.spec.clone(),
+                started_at: entry.started_at,
+            })
+            .collect()
+    }
+}
+
+/// Entries are removed as soon as their process exits, so any entry left is
+/// either running or stopping.
+fn refuse_live_key(
+    entries: &HashMap<ProcessKey, Entry>,
+    spec: &LaunchSpec,
+) -> Result<(), CommandError> {
+    match entries.get(&spec.key()) {
+        Some(_) => Err(CommandError::AlreadyRunning(spec.key().to_string())),
+        None => Ok(()),
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{FakeChild, FakeSpawner};
+    use std::sync::atomic::Ordering;
+
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    fn spec(app_id: &str) -> LaunchSpec {
+        LaunchSpec {
+            app_id: app_id.to_string(),
+            executable_name: "game.exe".to_string(),
+            game_name: "Game".to_string(),
+            program: PathBuf::from("games").join(app_id).join("game.exe"),
+            args: vec!["--title".to_string(), "Game".to_string()],
+            current_dir: PathBuf::from("games").join(app_id),
+        }
+    }
+
+    fn key(app_id: &str) -> ProcessKey {
+        ProcessKey::new(app_id, "game.exe")
+    }
+
+    fn states(supervisor: &ProcessSupervisor) -> Vec<(ProcessKey, u32, ProcessState)> {
+        supervisor
+            .snapshot()
+            .into_iter()
+            .map(|process| (process.key, process.pid, process.state))
+            .collect()
+    }
+
+    fn next_exit(events: &Receiver<SupervisorEvent>) -> SupervisorEvent {
+        loop {
+            match events.recv_timeout(TIMEOUT).expect("no event received") {
+                event @ (SupervisorEvent::Exited { .. } | SupervisorEvent::WaitFailed { .. }) => {
+                    return event
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    fn kinds(events: &Receiver<SupervisorEvent>) -> Vec<&'static str> {
+        events
+            .try_iter()
+            .map(|event| match event {
+                SupervisorEvent::Started { .. } => "started",
+                SupervisorEvent::StopRequested { .. } => "stop_requested",
+                SupervisorEvent::ForceKilled { .. } => "force_killed",
+                SupervisorEvent::StopFinished { .. } => "stop_finished",
+                SupervisorEvent::Exited { .. } => "exited",
+                SupervisorEvent::WaitFailed { .. } => "wait_failed",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spawn_registers_and_reports_start() {
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        let pid = supervisor.spawn(spec("1")).unwrap();
+
+        assert!(matches!(
+            events.recv_timeout(TIMEOUT).unwrap(),
+            SupervisorEvent::Started { spec: s, pid: p, .. } if s == spec("1") && p == pid
+        ));
+        assert_eq!(
+            states(&supervisor),
+            vec![(key("1"), pid, ProcessState::Running)]
+        );
+    }
+
+    #[test]
+    fn external_exit_is_reported_and_removed() {
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        let pid = supervisor.spawn(spec("1")).unwrap();
+        spawner.exit(pid, Ok(Some(3)));
+
+        assert!(matches!(
+            next_exit(&events),
+            SupervisorEvent::Exited {
+                spec: s,
+                pid: p,
+                exit_code: Some(3),
+                stop_requested: false,
+                ..
+            } if s == spec("1") && p == pid
+        ));
+        assert!(supervisor.snapshot().is_empty());
+    }
+
+    #[test]
+    fn wait_error_is_reported() {
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        let pid = supervisor.spawn(spec("1")).unwrap();
+        spawner.exit(pid, Err(io::Error::other("boom")));
+
+        assert!(matches!(
+            next_exit(&events),
+            SupervisorEvent::WaitFailed { pid: p, .. } if p == pid
+        ));
+        assert!(supervisor.snapshot().is_empty());
+    }
+
+    #[test]
+    fn failed_spawn_registers_nothing() {
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, events) = ProcessSupervisor::new(spawner);
+
+        let missing = LaunchSpec {
+            executable_name: "missing.exe".to_string(),
+            ..spec("1")
+        };
+        assert!(matches!(
+            supervisor.spawn(missing),
+            Err(CommandError::SpawnFailed(_))
+        ));
+        assert!(supervisor.snapshot().is_empty());
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn graceful_stop_does_not_force_kill() {
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        let pid = supervisor.spawn(spec("1")).unwrap();
+        assert_eq!(
+            supervisor.stop(&key("1"), Duration::from_millis(200)),
+            StopRequest::Stopping(pid)
+        );
+
+        assert!(matches!(
+            next_exit(&events),
+            SupervisorEvent::Exited {
+                exit_code: Some(0),
+                stop_requested: true,
+                ..
+            }
+        ));
+        std::thread::sleep(Duration::from_millis(300));
+        assert_eq!(spawner.calls(), vec![format!("terminate {pid}")]);
+    }
+
+    #[test]
+    fn stop_force_kills_after_grace_period() {
+        let spawner = Arc::new(FakeSpawner {
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        let pid = supervisor.spawn(spec("1")).unwrap();
+        supervisor.stop(&key("1"), Duration::from_millis(50));
+        assert_eq!(
+            states(&supervisor),
+            vec![(key("1"), pid, ProcessState::Stopping)]
+        );
+
+        assert!(matches!(
+            next_exit(&events),
+            SupervisorEvent::Exited {
+                exit_code: None,
+                ..
+            }
+        ));
+        assert_eq!(
+            spawner.calls(),
+            vec![format!("terminate {pid}"), format!("kill {pid}")]
+        );
+    }
+
+    #[test]
+    fn stop_reports_each_step() {
+        let spawner = Arc::new(FakeSpawner {
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        supervisor.spawn(spec("1")).unwrap();
+        supervisor.stop(&key("1"), Duration::from_millis(50));
+        // The kill and the exit come from different threads; wait for both
+        std::thread::sleep(Duration::from_millis(300));
+
+        let mut kinds = kinds(&events);
+        kinds[2..].sort();
+        assert_eq!(
+            kinds,
+            vec![
+                "started",
+                "stop_requested",
+                "exited",
+                "force_killed",
+                "stop_finished"
+            ]
+        );
+    }
+
+    #[test]
+    fn stop_wait_reports_how_the_process_ended() {
+        let short = Duration::from_millis(50);
+
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, _events) = ProcessSupervisor::new(spawner);
+        supervisor.spawn(spec("1")).unwrap();
+        assert_eq!(
+            supervisor.stop_wait(&key("1"), short, short),
+            Some(StopOutcome::Graceful)
+        );
+        assert_eq!(supervisor.stop_wait(&key("1"), short, short), None);
+
+        let spawner = Arc::new(FakeSpawner {
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let (supervisor, _events) = ProcessSupervisor::new(spawner);
+        supervisor.spawn(spec("1")).unwrap();
+        assert_eq!(
+            supervisor.stop_wait(&key("1"), short, TIMEOUT),
+            Some(StopOutcome::Forced)
+        );
+
+        let spawner = Arc::new(FakeSpawner {
+            ignore_terminate: true,
+            ignore_kill: true,
+            ..Default::default()
+        });
+        let (supervisor, _events) = ProcessSupervisor::new(spawner);
+        supervisor.spawn(spec("1")).unwrap();
+        assert_eq!(
+            supervisor.stop_wait(&key("1"), short, short),
+            Some(StopOutcome::Failed)
+        );
+    }
+
+    #[test]
+    fn stop_wait_joins_a_stop_in_progress() {
+        let spawner = Arc::new(FakeSpawner {
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        let pid = supervisor.spawn(spec("1")).unwrap();
+        supervisor.stop(&key("1"), TIMEOUT);
+        let waiter = {
+            let supervisor = supervisor.clone();
+            std::thread::spawn(move || supervisor.stop_wait(&key("1"), TIMEOUT, TIMEOUT))
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        spawner.exit(pid, Ok(Some(0)));
+
+        assert_eq!(waiter.join().unwrap(), Some(StopOutcome::AlreadyExited));
+        // Only the first stop closes the process and reports a result
+        std::thread::sleep(Duration::from_millis(50));
+        let kinds = kinds(&events);
+        assert_eq!(kinds.iter().filter(|k| **k == "stop_requested").count(), 1);
+        assert_eq!(kinds.iter().filter(|k| **k == "stop_finished").count(), 1);
+        assert_eq!(spawner.calls(), vec![format!("terminate {pid}")]);
+    }
+
+    #[test]
+    fn repeated_and_unknown_stops() {
+        let spawner = Arc::new(FakeSpawner {
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let (supervisor, _events) = ProcessSupervisor::new(spawner);
+
+        let pid = supervisor.spawn(spec("1")).unwrap();
+        supervisor.stop(&key("1"), TIMEOUT);
+        assert_eq!(
+            supervisor.stop(&key("1"), TIMEOUT),
+            StopRequest::AlreadyStopping(pid)
+        );
+        assert_eq!(supervisor.stop(&key("2"), TIMEOUT), StopRequest::Unknown);
+    }
+
+    #[test]
+    fn stop_all_closes_everything_within_bounds() {
+        let spawner = Arc::new(FakeSpawner {
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let (supervisor, _events) = ProcessSupervisor::new(spawner.clone());
+
+        let first = supervisor.spawn(spec("1")).unwrap();
+        let second = supervisor.spawn(spec("2")).unwrap();
+        // The first one honours the close request, the second needs a kill
+        spawner.exit(first, Ok(Some(0)));
+
+        let started = Instant::now();
+        let survivors = supervisor.stop_all(Duration::from_millis(50), Duration::from_millis(50));
+
+        assert_eq!(survivors, 0);
+        assert!(started.elapsed() < TIMEOUT);
+        assert!(spawner.calls().contains(&format!("kill {second}")));
+        assert!(!spawner.calls().contains(&format!("kill {first}")));
+        assert!(supervisor.snapshot().is_empty());
+    }
+
+    #[test]
+    fn stop_all_returns_with_exits_already_published() {
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+        supervisor.spawn(spec("1")).unwrap();
+        supervisor.spawn(spec("2")).unwrap();
+
+        supervisor.stop_all(Duration::from_millis(50), Duration::from_millis(50));
+
+        let kinds = kinds(&events);
+        assert_eq!(kinds.iter().filter(|kind| **kind == "exited").count(), 2);
+        assert_eq!(supervisor.events_sent(), kinds.len() as u64);
+    }
+
+    #[test]
+    fn adopted_child_keeps_its_start_time() {
+        let spawner = Arc::new(FakeSpawner::default());
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        let (sender, exit) = mpsc::channel();
+        let pid = supervisor.adopt(
+            spec("1"),
+            Box::new(FakeChild { pid: 42, exit }),
+            1_700_000_000,
+        );
+
+        assert_eq!(pid.unwrap(), 42);
+        assert!(matches!(
+            events.recv_timeout(TIMEOUT).unwrap(),
+            SupervisorEvent::Started {
+                pid: 42,
+                started_at: 1_700_000_000,
+                ..
+            }
+        ));
+        let process = &supervisor.snapshot()[0];
+        assert_eq!(process.started_at, 1_700_000_000);
+        assert_eq!(process.uptime_secs(1_700_000_090), 90);
+        assert_eq!(process.uptime_secs(1_600_000_000), 0);
+
+        sender.send(Ok(None)).unwrap();
+        assert!(matches!(
+            next_exit(&events),
+            SupervisorEvent::Exited {
+                pid: 42,
+                started_at: 1_700_000_000,
+                ..
+            }
+        ));
+        assert!(supervisor.snapshot().is_empty());
+    }
+
+    #[test]
+    fn live_key_is_not_spawned_again() {
+        let spawner = Arc::new(FakeSpawner {
+            ignore_terminate: true,
+            ..Default::default()
+        });
+        let (supervisor, events) = ProcessSupervisor::new(spawner.clone());
+
+        let pid = supervisor.spawn(spec("1")).unwrap();
+        assert!(matches!(
+            supervisor.spawn(spec("1")),
+            Err(CommandError::AlreadyRunning(key)) if key == "1:game.exe"
+        ));
+        // Still refused while it is being stopped
+        supervisor.stop(&key("1"), TIMEOUT);
+        assert!(matches!(
+            supervisor.spawn(spec("1")),
+            Err(CommandError::AlreadyRunning(_))
+        ));
+        let (_sender, exit) = mpsc::channel();
+        let adopted = supervisor.adopt(spec("1"), Box::new(FakeChild { pid: 42, exit }), 0);
+        assert!(matches!(adopted, Err(CommandError::AlreadyRunning(_))));
+        assert_eq!(spawner.next_pid.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            states(&supervisor),
+            vec![(key("1"), pid, ProcessState::Stopping)]
+        );
+
+        // Once it is gone the key can be launched again
+        spawner.exit(pid, Ok(Some(0)));
+        assert!(matches!(next_exit(&events), SupervisorEvent::Exited { pid: p, .. } if p == pid));
+        let relaunched = supervisor.spawn(spec("1")).unwrap();
+        assert_eq!(
+            states(&supervisor),
+            vec![(key("1"), relaunched, ProcessState::Running)]
+        );
+    }
+}
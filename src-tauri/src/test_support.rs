@@ -0,0 +1,74 @@
+use crate::supervisor::{LaunchSpec, Spawner, SupervisedChild};
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+
+/// Fake child whose exit is triggered by the spawner's terminate/kill or by the test.
+pub struct FakeChild {
+    pub pid: u32,
+    pub exit: Receiver<io::Result<Option<i32>>>,
+}
+
+impl SupervisedChild for FakeChild {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    fn wait(self: Box<Self>) -> io::Result<Option<i32>> {
+        self.exit.recv().unwrap_or(Ok(None))
+    }
+}
+
+/// Hands out pids from 100 up. Children run until `exit` is called with their
+/// pid, or until terminate/kill unless told to ignore them. Specs whose
+/// executable is `missing.exe` fail to start.
+#[derive(Default)]
+pub struct FakeSpawner {
+    pub next_pid: AtomicU32,
+    pub ignore_terminate: bool,
+    pub ignore_kill: bool,
+    pub exits: Mutex<HashMap<u32, Sender<io::Result<Option<i32>>>>>,
+    pub calls: Mutex<Vec<String>>,
+}
+
+impl FakeSpawner {
+    pub fn exit(&self, pid: u32, result: io::Result<Option<i32>>) {
+        if let Some(sender) = self.exits.lock().unwrap().remove(&pid) {
+            let _ = sender.send(result);
+        }
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl Spawner for FakeSpawner {
+    fn spawn(&self, spec: &LaunchSpec) -> io::Result<Box<dyn SupervisedChild>> {
+        if spec.executable_name == "missing.exe" {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "missing"));
+        }
+        let pid = self.next_pid.fetch_add(1, Ordering::SeqCst) + 100;
+        let (sender, exit) = mpsc::channel();
+        self.exits.lock().unwrap().insert(pid, sender);
+        Ok(Box::new(FakeChild { pid, exit }))
+    }
+
+    fn terminate(&self, pid: u32) -> io::Result<()> {
+        self.calls.lock().unwrap().push(format!("terminate {pid}"));
+        if !self.ignore_terminate {
+            self.exit(pid, Ok(Some(0)));
+        }
+        Ok(())
+    }
+
+    fn force_kill(&self, pid: u32) -> io::Result<()> {
+        self.calls.lock().unwrap().push(format!("kill {pid}"));
+        if !self.ignore_kill {
+            self.exit(pid, Ok(None));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,446 @@
+//! What the stub window shows and where, with no Win32 in it. `paint_window`
+//! in `src-win` only turns a [`LayoutModel`] into GDI calls.
+
+use discord_quest_i18n::{self as i18n, Lang};
+
+/// Same edges as a Win32 `RECT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Rect {
+    fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        Rect {
+            left: x,
+            top: y,
+            right: x + w.max(0),
+            bottom: y + h.max(0),
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+}
+
+/// A `COLORREF` value, so `0x00BBGGRR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Color(pub u32);
+
+const BACKGROUND: Color = Color(0x00161216);
+const ACCENT: Color = Color(0x00F26558); // #5865F2
+const GREEN: Color = Color(0x0087F257); // #57F287
+const TEXT: Color = Color(0x00F4F0F0);
+const MUTED: Color = Color(0x007A647A);
+const CARD_FILL: Color = Color(0x001E1A1E);
+const CARD_BORDER: Color = Color(0x002A262A);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontRole {
+    Title,
+    Label,
+    Value,
+    Timer,
+    Small,
+}
+
+impl FontRole {
+    pub const ALL: [FontRole; 5] = [
+        FontRole::Title,
+        FontRole::Label,
+        FontRole::Value,
+        FontRole::Timer,
+        FontRole::Small,
+    ];
+
+    /// Font height in pixels at 96 DPI.
+    fn base_height(self) -> i32 {
+        match self {
+            FontRole::Title => 22,
+            FontRole::Label | FontRole::Value => 13,
+            FontRole::Timer => 32,
+            FontRole::Small => 11,
+        }
+    }
+
+    pub fn height(self, scale: f32) -> i32 {
+        scaled(self.base_height(), scale)
+    }
+
+    /// GDI font weight.
+    pub fn weight(self) -> i32 {
+        match self {
+            FontRole::Title | FontRole::Timer => 700,
+            FontRole::Label => 600,
+            FontRole::Value | FontRole::Small => 400,
+        }
+    }
+
+    pub fn face(self) -> &'static str {
+        match self {
+            FontRole::Timer => "Cascadia Code",
+            _ => "Segoe UI",
+        }
+    }
+
+    /// Rough average advance of a narrow character, used to truncate without measuring.
+    fn char_width(self, scale: f32) -> i32 {
+        (self.height(scale) * 11 / 20).max(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextRun {
+    pub text: String,
+    pub rect: Rect,
+    pub color: Color,
+    pub font: FontRole,
+    pub align: Align,
+    pub vcenter: bool,
+}
+
+/// Drawn in order, later items on top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    Fill {
+        rect: Rect,
+        color: Color,
+    },
+    RoundedRect {
+        rect: Rect,
+        radius: i32,
+        fill: Color,
+        border: Color,
+    },
+    Line {
+        from: (i32, i32),
+        to: (i32, i32),
+        color: Color,
+    },
+    Text(TextRun),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutModel {
+    /// DPI / 96; fonts are created at [`FontRole::height`] for this scale.
+    pub scale: f32,
+    pub items: Vec<Item>,
+}
+
+fn scaled(value: i32, scale: f32) -> i32 {
+    (value as f32 * scale).round() as i32
+}
+
+impl LayoutModel {
+    /// Layout for a client area of `width` x `height` pixels.
+    pub fn new(
+        width: i32,
+        height: i32,
+        scale: f32,
+        lang: Lang,
+        title: &str,
+        elapsed_secs: u64,
+    ) -> Self {
+        let px = |value: i32| scaled(value, scale);
+        let mut items = Vec::new();
+        let text =
+            |text: &str, rect: Rect, color: Color, font: FontRole, align: Align, vcenter: bool| {
+                let max_units = (rect.width() / font.char_width(scale)).max(0) as usize;
+                Item::Text(TextRun {
+                    text: truncate(text, max_units),
+                    rect,
+                    color,
+                    font,
+                    align,
+                    vcenter,
+                })
+            };
+
+        let margin = px(20);
+        let inner_w = width - 2 * margin;
+
+        // ── Background and top accent bar ──
+        items.push(Item::Fill {
+            rect: Rect::new(0, 0, width, height),
+            color: BACKGROUND,
+        });
+        items.push(Item::Fill {
+            rect: Rect::new(0, 0, width, px(3)),
+            color: ACCENT,
+        });
+
+        // ── Status pill ──
+        items.push(Item::RoundedRect {
+            rect: Rect::new(margin, px(18), px(70), px(20)),
+            radius: px(10),
+            fill: Color(0x001A3A1A),
+            border: Color(0x00287F28),
+        });
+        items.push(text(
+            i18n::text(lang, "stub.active"),
+            Rect::new(px(26), px(18), px(60), px(20)),
+            GREEN,
+            FontRole::Label,
+            Align::Left,
+            true,
+        ));
+
+        // ── Game title ──
+        items.push(text(
+            title,
+            Rect::new(margin, px(48), inner_w, px(28)),
+            TEXT,
+            FontRole::Title,
+            Align::Left,
+            false,
+        ));
+
+        // ── Separator line ──
+        items.push(Item::Line {
+            from: (margin, px(84)),
+            to: (width - margin, px(84)),
+            color: Color(0x003A2A2A),
+        });
+
+        // ── Info cards ──
+        let card_y = px(96);
+        let card_h = px(52);
+        let gap = px(12);
+        let card_w = ((inner_w - gap) / 2).max(0);
+        let cards = [
+            ("stub.status_label", "stub.status_value", GREEN),
+            ("stub.kind_label", "stub.kind_value", Color(0x00B0A0B0)),
+        ];
+        for (i, (label, value, value_color)) in cards.into_iter().enumerate() {
+            let x = margin + i as i32 * (card_w + gap);
+            let text_x = x + px(10);
+            let text_w = card_w - px(20);
+            items.push(Item::RoundedRect {
+                rect: Rect::new(x, card_y, card_w, card_h),
+                radius: px(8),
+                fill: CARD_FILL,
+                border: CARD_BORDER,
+            });
+            items.push(text(
+                i18n::text(lang, label),
+                Rect::new(text_x, card_y + px(8), text_w, px(14)),
+                MUTED,
+                FontRole::Label,
+                Align::Left,
+                false,
+            ));
+            items.push(text(
+                i18n::text(lang, value),
+                Rect::new(text_x, card_y + px(26), text_w, px(16)),
+                value_color,
+                FontRole::Value,
+                Align::Left,
+                false,
+            ));
+        }
+
+        // ── Timer ──
+        let timer_y = card_y + card_h + px(16);
+        items.push(Item::RoundedRect {
+            rect: Rect::new(margin, timer_y, inner_w, px(58)),
+            radius: px(10),
+            fill: Color(0x00201820),
+            border: CARD_BORDER,
+        });
+        items.push(text(
+            i18n::text(lang, "stub.elapsed_label"),
+            Rect::new(margin + px(10), timer_y + px(6), inner_w - px(20), px(14)),
+            MUTED,
+            FontRole::Label,
+            Align::Left,
+            false,
+        ));
+        items.push(text(
+            &format_elapsed(elapsed_secs),
+            Rect::new(margin, timer_y + px(20), inner_w, px(38)),
+            TEXT,
+            FontRole::Timer,
+            Align::Center,
+            true,
+        ));
+
+        // ── Footer, anchored to the bottom edge ──
+        let footer_y = height - px(24);
+        items.push(Item::Fill {
+            rect: Rect::new(0, footer_y - 1, width, 1),
+            color: Color(0x002A222A),
+        });
+        items.push(text(
+            i18n::text(lang, "stub.footer"),
+            Rect::new(margin, footer_y + px(2), inner_w, px(16)),
+            Color(0x00504050),
+            FontRole::Small,
+            Align::Left,
+            false,
+        ));
+
+        LayoutModel { scale, items }
+    }
+
+    pub fn texts(&self) -> impl Iterator<Item = &TextRun> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Text(run) => Some(run),
+            _ => None,
+        })
+    }
+}
+
+pub fn format_elapsed(secs: u64) -> String {
+    let h = secs / 3600;
+    let m = (secs % 3600) / 60;
+    let s = secs % 60;
+    if h > 0 {
+        format!("{:02}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+/// CJK and other full-width characters take about two narrow widths.
+fn char_units(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Cuts `text` to `max_units` narrow widths, ending in "…" when something was cut.
+pub fn truncate(text: &str, max_units: usize) -> String {
+    if text.chars().map(char_units).sum::<usize>() <= max_units {
+        return text.to_string();
+    }
+    let mut units = 1; // the ellipsis
+    let mut out = String::new();
+    for c in text.chars() {
+        units += char_units(c);
+        if units > max_units {
+            break;
+        }
+        out.push(c);
+    }
+    if max_units > 0 {
+        out.push('…');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run<'a>(model: &'a LayoutModel, text: &str) -> &'a TextRun {
+        model
+            .texts()
+            .find(|run| run.text.starts_with(text))
+            .unwrap()
+    }
+
+    fn rounded_rects(model: &LayoutModel) -> Vec<Rect> {
+        model
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::RoundedRect { rect, .. } => Some(*rect),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cards_split_the_width_and_footer_follows_the_bottom() {
+        let model = LayoutModel::new(364, 221, 1.0, Lang::Es, "Game", 0);
+        let rects = rounded_rects(&model);
+        let (estado, tipo, timer) = (rects[1], rects[2], rects[3]);
+        assert_eq!(estado.left, 20);
+        assert_eq!(estado.width(), tipo.width());
+        assert_eq!(tipo.left - estado.right, 12);
+        assert!(tipo.right <= 364 - 20);
+        assert_eq!((timer.left, timer.right), (20, 344));
+
+        let footer = run(&model, "DiscordQuest - No cerrar").rect;
+        assert_eq!(footer.top, 221 - 24 + 2);
+        let taller = LayoutModel::new(364, 400, 1.0, Lang::Es, "Game", 0);
+        assert_eq!(
+            run(&taller, "DiscordQuest - No cerrar").rect.top,
+            400 - 24 + 2
+        );
+    }
+
+    #[test]
+    fn geometry_and_fonts_follow_the_dpi_scale() {
+        let normal = LayoutModel::new(364, 221, 1.0, Lang::Es, "Game", 0);
+        let large = LayoutModel::new(728, 442, 2.0, Lang::Es, "Game", 0);
+        let (a, b) = (run(&normal, "Game").rect, run(&large, "Game").rect);
+        assert_eq!(
+            (b.left, b.top, b.right, b.bottom),
+            (a.left * 2, a.top * 2, a.right * 2, a.bottom * 2)
+        );
+        assert_eq!(FontRole::Timer.height(1.5), 48);
+    }
+
+    #[test]
+    fn long_titles_are_cut_to_the_available_width() {
+        let title = "A Very Long Game Title That Goes On And On Forever";
+        let model = LayoutModel::new(364, 221, 1.0, Lang::Es, title, 0);
+        let shown = &run(&model, "A Very").text;
+        assert!(shown.ends_with('…'));
+        assert!(shown.chars().count() < title.chars().count());
+        assert_eq!(
+            run(
+                &LayoutModel::new(1200, 221, 1.0, Lang::Es, title, 0),
+                "A Very"
+            )
+            .text,
+            title
+        );
+    }
+
+    #[test]
+    fn truncation_counts_wide_characters_double() {
+        assert_eq!(truncate("abcdef", 6), "abcdef");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("原神原神", 5), "原神…");
+        assert_eq!(truncate("abc", 0), "");
+    }
+
+    #[test]
+    fn labels_follow_the_language() {
+        let model = LayoutModel::new(364, 221, 1.0, Lang::En, "Game", 0);
+        assert_eq!(run(&model, "TIME").text, "TIME ACTIVE");
+        assert_eq!(
+            run(&model, "DiscordQuest - ").text,
+            "DiscordQuest - Do not close this window"
+        );
+    }
+
+    #[test]
+    fn elapsed_shows_hours_only_when_needed() {
+        assert_eq!(format_elapsed(65), "01:05");
+        assert_eq!(format_elapsed(3_725), "01:02:05");
+        let model = LayoutModel::new(364, 221, 1.0, Lang::Es, "Game", 3_725);
+        assert_eq!(run(&model, "01:").align, Align::Center);
+    }
+}
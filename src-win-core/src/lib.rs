@@ -0,0 +1,8 @@
+//! The platform-neutral half of the `src-win` stub, kept out of that crate so
+//! it builds and is tested with the rest of the workspace.
+//!
+//! - [`layout`]: what the stub window draws and where.
+//! - [`wide`]: UTF-16 text for the `W` Win32 calls.
+
+pub mod layout;
+pub mod wide;
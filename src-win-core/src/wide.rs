@@ -0,0 +1,93 @@
+//! UTF-16 helpers for the `W` Win32 calls. Pure so they can be tested anywhere.
+
+/// Longest tooltip the notification area keeps, not counting the terminating NUL.
+pub const TOOLTIP_MAX_UNITS: usize = 127;
+
+/// `text` as UTF-16 without a terminator, for calls that take a length (`DrawTextW`).
+/// A NUL inside the text becomes U+FFFD so it cannot cut the string short.
+pub fn to_wide(text: &str) -> Vec<u16> {
+    text.encode_utf16()
+        .map(|unit| if unit == 0 { 0xFFFD } else { unit })
+        .collect()
+}
+
+/// `text` as a NUL-terminated UTF-16 string, for `PCWSTR` parameters.
+pub fn to_wide_nul(text: &str) -> Vec<u16> {
+    let mut wide = to_wide(text);
+    wide.push(0);
+    wide
+}
+
+/// A game name fit for display: control characters removed and surrounding
+/// whitespace trimmed, falling back to `DiscordQuest` when nothing is left.
+pub fn clean_title(title: &str) -> String {
+    let cleaned: String = title.chars().filter(|c| !c.is_control()).collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        "DiscordQuest".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// `text` cut to at most `max_units` UTF-16 units without splitting a
+/// surrogate pair, ending in "…" when something was cut.
+pub fn truncate_utf16(text: &str, max_units: usize) -> String {
+    if text.encode_utf16().count() <= max_units {
+        return text.to_string();
+    }
+    let mut units = 1; // the ellipsis
+    let mut out = String::new();
+    for c in text.chars() {
+        units += c.len_utf16();
+        if units > max_units {
+            break;
+        }
+        out.push(c);
+    }
+    out.push('…');
+    out
+}
+
+pub fn tooltip(title: &str) -> String {
+    truncate_utf16(&format!("DiscordQuest - {}", title), TOOLTIP_MAX_UNITS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_non_latin_titles() {
+        assert_eq!(to_wide("原神"), vec![0x539F, 0x795E]);
+        assert_eq!(to_wide_nul("한"), vec![0xD55C, 0]);
+        // Outside the BMP: one char, two units
+        assert_eq!(to_wide("𠮷"), vec![0xD842, 0xDFB7]);
+        assert_eq!(
+            String::from_utf16(&to_wide("ゼルダの伝説")).unwrap(),
+            "ゼルダの伝説"
+        );
+    }
+
+    #[test]
+    fn interior_nul_does_not_end_the_string() {
+        assert_eq!(to_wide_nul("a\0b"), vec![0x61, 0xFFFD, 0x62, 0]);
+    }
+
+    #[test]
+    fn titles_are_cleaned() {
+        assert_eq!(clean_title("  Elden\0 Ring\n "), "Elden Ring");
+        assert_eq!(clean_title("\0\t"), "DiscordQuest");
+        assert_eq!(clean_title("崩壊：スターレイル"), "崩壊：スターレイル");
+    }
+
+    #[test]
+    fn tooltip_fits_and_keeps_surrogate_pairs_whole() {
+        let long = "𠮷".repeat(100);
+        let tip = tooltip(&long);
+        assert!(tip.encode_utf16().count() <= TOOLTIP_MAX_UNITS);
+        assert!(tip.ends_with('…'));
+        assert!(String::from_utf16(&to_wide(&tip)).is_ok());
+        assert_eq!(tooltip("Hades"), "DiscordQuest - Hades");
+    }
+}
@@ -1,113 +1,143 @@
 #![windows_subsystem = "windows"] 
 
-use windows::Win32::Foundation::{COLORREF, HINSTANCE, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Foundation::{
+    CloseHandle, COLORREF, ERROR_INVALID_PARAMETER, HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, RECT, WPARAM,
+};
 use windows::Win32::UI::WindowsAndMessaging::{
-    CreateWindowExA, DefWindowProcA, DispatchMessageA, GetMessageA, PostQuitMessage, 
-    RegisterClassA, ShowWindow, TranslateMessage, SetTimer,
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, PostQuitMessage, 
+    RegisterClassW, ShowWindow, TranslateMessage, SetTimer, KillTimer, PostMessageW, SetForegroundWindow,
     CW_USEDEFAULT, MSG, SW_SHOWNORMAL, WINDOW_EX_STYLE, 
-    WM_CREATE, WM_DESTROY, WM_PAINT, WM_TIMER, WNDCLASSA, WS_CAPTION, 
+    WM_APP, WM_CLOSE, WM_CREATE, WM_DESTROY, WM_PAINT, WM_TIMER, WNDCLASSW, WS_CAPTION, 
     WS_SYSMENU, WS_MINIMIZEBOX,
 };
 use windows::Win32::Graphics::Gdi::{
     BeginPaint, EndPaint, CreateSolidBrush, FillRect, SelectObject, DeleteObject,
-    CreateFontA, SetBkMode, SetTextColor, DrawTextA,
+    CreateFontW, SetBkMode, SetTextColor, DrawTextW,
     CreatePen, MoveToEx, LineTo, RoundRect,
-    HDC, HFONT, PAINTSTRUCT, TRANSPARENT, DRAW_TEXT_FORMAT, DT_LEFT, DT_SINGLELINE, DT_VCENTER, 
-    DT_CENTER, PS_SOLID, FW_BOLD, FW_NORMAL, FW_SEMIBOLD,
+    HDC, HFONT, PAINTSTRUCT, TRANSPARENT, DT_LEFT, DT_SINGLELINE, DT_VCENTER,
+    DT_CENTER, DT_NOPREFIX, PS_SOLID,
     DEFAULT_CHARSET, OUT_DEFAULT_PRECIS, CLIP_DEFAULT_PRECIS, CLEARTYPE_QUALITY, 
     DEFAULT_PITCH, FF_SWISS, FONT_CHARSET, FONT_OUTPUT_PRECISION, 
     FONT_CLIP_PRECISION, FONT_QUALITY,
 };
-use windows::Win32::System::LibraryLoader::GetModuleHandleA;
-use windows::core::PCSTR;
-use std::ffi::CString;
-use std::env;
-use std::sync::atomic::{AtomicU64, Ordering};
+use windows::Win32::UI::HiDpi::GetDpiForWindow;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::{OpenProcess, WaitForSingleObject, INFINITE, PROCESS_SYNCHRONIZE};
+use windows::core::{HRESULT, PCWSTR};
+use std::sync::atomic::{AtomicIsize, AtomicU64, Ordering};
 use std::sync::OnceLock;
 use std::time::SystemTime;
+use discord_quest_core::StubArgs;
+use discord_quest_i18n::{self as i18n, Lang};
+use discord_quest_ipc::{Event, Handler, Request, Response, Server, StubStatus};
 
 mod tray;
+use discord_quest_win_core::{layout, wide};
+use layout::{Align, FontRole, Item, LayoutModel, TextRun};
+use wide::{clean_title, to_wide, to_wide_nul, tooltip};
 use tray::create_tray_icon;
 
 const WIDTH: i32 = 380;
 const HEIGHT: i32 = 260;
 const TIMER_ID: usize = 1;
+// Posted by the IPC thread; the window can only be shown from the UI thread
+const WM_SHOW_FROM_APP: u32 = WM_APP + 1;
 
 static START_TIME: AtomicU64 = AtomicU64::new(0);
 static GAME_TITLE: OnceLock<String> = OnceLock::new();
+static UI_LANG: OnceLock<Lang> = OnceLock::new();
+static MAIN_WINDOW: AtomicIsize = AtomicIsize::new(0);
+// 0 means no limit
+static MAX_RUNTIME_SECS: AtomicU64 = AtomicU64::new(0);
+
+fn create_font(role: FontRole, scale: f32) -> HFONT {
+    unsafe {
+        let font_name = to_wide_nul(role.face());
+        CreateFontW(
+            role.height(scale), 0, 0, 0,
+            role.weight(),
+            0, 0, 0,
+            FONT_CHARSET(DEFAULT_CHARSET.0),
+            FONT_OUTPUT_PRECISION(OUT_DEFAULT_PRECIS.0),
+            FONT_CLIP_PRECISION(CLIP_DEFAULT_PRECIS.0),
+            FONT_QUALITY(CLEARTYPE_QUALITY.0),
+            (DEFAULT_PITCH.0 as u32) | (FF_SWISS.0 as u32),
+            PCWSTR(font_name.as_ptr()),
+        )
+    }
+}
+
+fn elapsed_secs() -> u64 {
+    let start = START_TIME.load(Ordering::Relaxed);
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
+    now.saturating_sub(start)
+}
 
-#[derive(Debug)]
-struct Config {
-    title: String,
+fn close_main_window() {
+    let hwnd = HWND(MAIN_WINDOW.load(Ordering::Relaxed) as *mut _);
+    let _ = unsafe { PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)) };
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Config {
-            title: "DiscordQuest".to_string(),
+/// Closes the window once `parent_pid` exits, so a crashed or killed app
+/// does not leave the stub running. Only watches when the app asked for it.
+fn watch_parent(parent_pid: u32) {
+    let parent = unsafe { OpenProcess(PROCESS_SYNCHRONIZE, false, parent_pid) };
+    match parent {
+        Ok(handle) => {
+            // HANDLE is not Send; the raw value is fine to wait on from another thread
+            let raw = handle.0 as isize;
+            std::thread::spawn(move || unsafe {
+                let handle = HANDLE(raw as *mut _);
+                WaitForSingleObject(handle, INFINITE);
+                let _ = CloseHandle(handle);
+                close_main_window();
+            });
         }
+        // No such process: the parent is already gone
+        Err(e) if e.code() == HRESULT::from_win32(ERROR_INVALID_PARAMETER.0) => close_main_window(),
+        Err(e) => eprintln!("Could not watch parent process {}: {}", parent_pid, e),
     }
 }
 
-fn parse_args() -> Config {
-    let args: Vec<String> = env::args().collect();
-    let mut config = Config::default();
-    
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--title" => {
-                if i + 1 < args.len() {
-                    config.title = args[i + 1].clone();
-                    i += 2;
-                } else {
-                    i += 1;
-                }
-            }
-            _ => {
-                i += 1;
-            }
+/// Answers the app over the control channel (see `discord-quest-ipc`).
+struct StubControl;
+
+impl StubControl {
+    fn post(msg: u32) -> Response {
+        let hwnd = HWND(MAIN_WINDOW.load(Ordering::Relaxed) as *mut _);
+        match unsafe { PostMessageW(Some(hwnd), msg, WPARAM(0), LPARAM(0)) } {
+            Ok(()) => Response::Done,
+            Err(e) => Response::Error { message: e.to_string() },
         }
     }
-    
-    config
 }
 
-fn create_font(_hdc: HDC, size: i32, weight: i32, name: &str) -> HFONT {
-    unsafe {
-        let font_name = CString::new(name).unwrap_or_else(|_| CString::new("Segoe UI").unwrap());
-        CreateFontA(
-            size, 0, 0, 0,
-            weight,
-            0, 0, 0,
-            FONT_CHARSET(DEFAULT_CHARSET.0),
-            FONT_OUTPUT_PRECISION(OUT_DEFAULT_PRECIS.0),
-            FONT_CLIP_PRECISION(CLIP_DEFAULT_PRECIS.0),
-            FONT_QUALITY(CLEARTYPE_QUALITY.0),
-            (DEFAULT_PITCH.0 as u32) | (FF_SWISS.0 as u32),
-            PCSTR(font_name.as_ptr() as *const u8),
-        )
+impl Handler for StubControl {
+    fn handle(&self, request: Request) -> Response {
+        match request {
+            Request::Ping => Response::Pong,
+            Request::GetStatus => Response::Status(StubStatus {
+                pid: std::process::id(),
+                title: GAME_TITLE.get().cloned().unwrap_or_default(),
+                elapsed_secs: elapsed_secs(),
+            }),
+            Request::Close => Self::post(WM_CLOSE),
+            Request::ShowWindow => Self::post(WM_SHOW_FROM_APP),
+        }
     }
 }
 
-fn format_elapsed(secs: u64) -> String {
-    let h = secs / 3600;
-    let m = (secs % 3600) / 60;
-    let s = secs % 60;
-    if h > 0 {
-        format!("{:02}:{:02}:{:02}", h, m, s)
-    } else {
-        format!("{:02}:{:02}", m, s)
-    }
+fn to_rect(rect: layout::Rect) -> RECT {
+    RECT { left: rect.left, top: rect.top, right: rect.right, bottom: rect.bottom }
 }
 
-fn draw_rounded_rect(hdc: HDC, left: i32, top: i32, right: i32, bottom: i32, radius: i32, fill_color: COLORREF, border_color: COLORREF) {
+fn draw_rounded_rect(hdc: HDC, rect: layout::Rect, radius: i32, fill_color: COLORREF, border_color: COLORREF) {
     unsafe {
         let brush = CreateSolidBrush(fill_color);
         let pen = CreatePen(PS_SOLID, 1, border_color);
         let old_brush = SelectObject(hdc, brush.into());
         let old_pen = SelectObject(hdc, pen.into());
-        let _ = RoundRect(hdc, left, top, right, bottom, radius, radius);
+        let _ = RoundRect(hdc, rect.left, rect.top, rect.right, rect.bottom, radius, radius);
         SelectObject(hdc, old_brush);
         SelectObject(hdc, old_pen);
         let _ = DeleteObject(brush.into());
@@ -115,15 +145,22 @@ fn draw_rounded_rect(hdc: HDC, left: i32, top: i32, right: i32, bottom: i32, rad
     }
 }
 
-fn draw_text_at(hdc: HDC, text: &str, x: i32, y: i32, w: i32, h: i32, color: COLORREF, font: HFONT, flags: DRAW_TEXT_FORMAT) {
+fn draw_text_run(hdc: HDC, run: &TextRun, font: HFONT) {
+    let mut flags = DT_SINGLELINE | DT_NOPREFIX;
+    flags |= match run.align {
+        Align::Left => DT_LEFT,
+        Align::Center => DT_CENTER,
+    };
+    if run.vcenter {
+        flags |= DT_VCENTER;
+    }
     unsafe {
         let old_font = SelectObject(hdc, font.into());
-        SetTextColor(hdc, color);
+        SetTextColor(hdc, COLORREF(run.color.0));
         SetBkMode(hdc, TRANSPARENT);
-        let cstr = CString::new(text).unwrap_or_else(|_| CString::new("?").unwrap());
-        let bytes = cstr.as_bytes();
-        let mut rc = RECT { left: x, top: y, right: x + w, bottom: y + h };
-        DrawTextA(hdc, &mut Vec::from(bytes), &mut rc, flags);
+        let mut text = to_wide(&run.text);
+        let mut rc = to_rect(run.rect);
+        DrawTextW(hdc, &mut text, &mut rc, flags);
         SelectObject(hdc, old_font);
     }
 }
@@ -132,105 +169,42 @@ fn paint_window(hwnd: HWND) {
     unsafe {
         let mut ps = PAINTSTRUCT::default();
         let hdc = BeginPaint(hwnd, &mut ps);
-        
+
         let mut client_rect = RECT::default();
         let _ = windows::Win32::UI::WindowsAndMessaging::GetClientRect(hwnd, &mut client_rect);
-        let cw = client_rect.right;
-        let ch = client_rect.bottom;
-
-        // ── Background ──
-        let bg_brush = CreateSolidBrush(COLORREF(0x00161216));
-        FillRect(hdc, &client_rect, bg_brush);
-        let _ = DeleteObject(bg_brush.into());
-
-        // ── Top accent bar ──
-        let accent_rect = RECT { left: 0, top: 0, right: cw, bottom: 3 };
-        let accent_brush = CreateSolidBrush(COLORREF(0x00F26558)); // #5865F2 in BGR
-        FillRect(hdc, &accent_rect, accent_brush);
-        let _ = DeleteObject(accent_brush.into());
-
-        // ── Fonts ──
-        let font_title = create_font(hdc, 22, FW_BOLD.0 as i32, "Segoe UI");
-        let font_label = create_font(hdc, 13, FW_SEMIBOLD.0 as i32, "Segoe UI");
-        let font_value = create_font(hdc, 13, FW_NORMAL.0 as i32, "Segoe UI");
-        let font_timer = create_font(hdc, 32, FW_BOLD.0 as i32, "Cascadia Code");
-        let font_small = create_font(hdc, 11, FW_NORMAL.0 as i32, "Segoe UI");
-
+        let scale = GetDpiForWindow(hwnd).max(96) as f32 / 96.0;
         let title = GAME_TITLE.get().map(|s| s.as_str()).unwrap_or("DiscordQuest");
-        let dt_left_single = DT_LEFT | DT_SINGLELINE | DT_VCENTER;
-        let dt_left = DT_LEFT | DT_SINGLELINE;
-        let dt_center = DT_CENTER | DT_SINGLELINE | DT_VCENTER;
-
-        // ── Status pill ──  
-        draw_rounded_rect(hdc, 20, 18, 90, 38, 10, 
-            COLORREF(0x001A3A1A),
-            COLORREF(0x00287F28),
-        );
-        let dot_color = COLORREF(0x0087F257); // #57F287 green
-        draw_text_at(hdc, "* Activo", 26, 18, 60, 20, dot_color, font_label, dt_left_single);
-
-        // ── Game title ──
-        draw_text_at(hdc, title, 20, 48, cw - 40, 28, COLORREF(0x00F4F0F0), font_title, dt_left);
-
-        // ── Separator line ──
-        let sep_pen = CreatePen(PS_SOLID, 1, COLORREF(0x003A2A2A));
-        let old_pen = SelectObject(hdc, sep_pen.into());
-        let _ = MoveToEx(hdc, 20, 84, None);
-        let _ = LineTo(hdc, cw - 20, 84);
-        SelectObject(hdc, old_pen);
-        let _ = DeleteObject(sep_pen.into());
-
-        // ── Info cards area ──
-        let card_y = 96;
-        let card_h = 52;
-        let half_w = (cw - 52) / 2;
-        
-        // Card 1: Estado
-        draw_rounded_rect(hdc, 20, card_y, 20 + half_w, card_y + card_h, 8,
-            COLORREF(0x001E1A1E),
-            COLORREF(0x002A262A),
-        );
-        draw_text_at(hdc, "ESTADO", 30, card_y + 8, half_w - 20, 14, COLORREF(0x007A647A), font_label, dt_left);
-        draw_text_at(hdc, "Simulando", 30, card_y + 26, half_w - 20, 16, COLORREF(0x0087F257), font_value, dt_left);
-
-        // Card 2: Tipo
-        let card2_x = 20 + half_w + 12;
-        draw_rounded_rect(hdc, card2_x, card_y, card2_x + half_w, card_y + card_h, 8,
-            COLORREF(0x001E1A1E),
-            COLORREF(0x002A262A),
-        );
-        draw_text_at(hdc, "TIPO", card2_x + 10, card_y + 8, half_w - 20, 14, COLORREF(0x007A647A), font_label, dt_left);
-        draw_text_at(hdc, "Quest Runner", card2_x + 10, card_y + 26, half_w - 20, 16, COLORREF(0x00B0A0B0), font_value, dt_left);
-
-        // ── Timer section ──
-        let timer_y = card_y + card_h + 16;
-        draw_rounded_rect(hdc, 20, timer_y, cw - 20, timer_y + 58, 10,
-            COLORREF(0x00201820), 
-            COLORREF(0x002A262A),
-        );
-        draw_text_at(hdc, "TIEMPO ACTIVO", 30, timer_y + 6, 120, 14, COLORREF(0x007A647A), font_label, dt_left);
-        
-        let start = START_TIME.load(Ordering::Relaxed);
-        let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
-        let elapsed = if now > start { now - start } else { 0 };
-        let time_str = format_elapsed(elapsed);
-        draw_text_at(hdc, &time_str, 20, timer_y + 20, cw - 40, 38, COLORREF(0x00F4F0F0), font_timer, dt_center);
-
-        // ── Footer ──
-        let footer_y = ch - 24;
-        let footer_sep = RECT { left: 0, top: footer_y - 1, right: cw, bottom: footer_y };
-        let footer_line = CreateSolidBrush(COLORREF(0x002A222A));
-        FillRect(hdc, &footer_sep, footer_line);
-        let _ = DeleteObject(footer_line.into());
-        
-        draw_text_at(hdc, "DiscordQuest - No cerrar esta ventana", 20, footer_y + 2, cw - 40, 16, COLORREF(0x00504050), font_small, dt_left);
+        let lang = UI_LANG.get().copied().unwrap_or_default();
+        let model = LayoutModel::new(client_rect.right, client_rect.bottom, scale, lang, title, elapsed_secs());
+
+        let fonts: Vec<(FontRole, HFONT)> = FontRole::ALL.iter().map(|&role| (role, create_font(role, scale))).collect();
+        let font_for = |role: FontRole| fonts.iter().find(|(r, _)| *r == role).map(|(_, f)| *f).unwrap_or_default();
+
+        for item in &model.items {
+            match item {
+                Item::Fill { rect, color } => {
+                    let brush = CreateSolidBrush(COLORREF(color.0));
+                    FillRect(hdc, &to_rect(*rect), brush);
+                    let _ = DeleteObject(brush.into());
+                }
+                Item::RoundedRect { rect, radius, fill, border } => {
+                    draw_rounded_rect(hdc, *rect, *radius, COLORREF(fill.0), COLORREF(border.0));
+                }
+                Item::Line { from, to, color } => {
+                    let pen = CreatePen(PS_SOLID, 1, COLORREF(color.0));
+                    let old_pen = SelectObject(hdc, pen.into());
+                    let _ = MoveToEx(hdc, from.0, from.1, None);
+                    let _ = LineTo(hdc, to.0, to.1);
+                    SelectObject(hdc, old_pen);
+                    let _ = DeleteObject(pen.into());
+                }
+                Item::Text(run) => draw_text_run(hdc, run, font_for(run.font)),
+            }
+        }
 
-        // ── Cleanup fonts ──
-        let _ = DeleteObject(font_title.into());
-        let _ = DeleteObject(font_label.into());
-        let _ = DeleteObject(font_value.into());
-        let _ = DeleteObject(font_timer.into());
-        let _ = DeleteObject(font_small.into());
+        for (_, font) in fonts {
+            let _ = DeleteObject(font.into());
+        }
 
         let _ = EndPaint(hwnd, &ps);
     }
@@ -250,6 +224,11 @@ unsafe extern "system" fn window_proc(
         }
         WM_TIMER => {
             if wparam.0 == TIMER_ID {
+                let max_runtime = MAX_RUNTIME_SECS.load(Ordering::Relaxed);
+                if max_runtime > 0 && elapsed_secs() >= max_runtime {
+                    let _ = KillTimer(Some(hwnd), TIMER_ID);
+                    let _ = PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0));
+                }
                 // Repaint to update the timer
                 let _ = windows::Win32::Graphics::Gdi::InvalidateRect(Some(hwnd), None, false);
             }
@@ -259,40 +238,45 @@ unsafe extern "system" fn window_proc(
             paint_window(hwnd);
             LRESULT(0)
         }
+        WM_SHOW_FROM_APP => {
+            let _ = ShowWindow(hwnd, SW_SHOWNORMAL);
+            let _ = SetForegroundWindow(hwnd);
+            LRESULT(0)
+        }
         WM_DESTROY => {
             PostQuitMessage(0);
             LRESULT(0)
         }
-        _ => DefWindowProcA(hwnd, msg, wparam, lparam),
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
 }
 
 fn create_native_window(title: &str) -> Result<(HWND, HINSTANCE), Box<dyn std::error::Error>> {
     unsafe {
-        let instance = GetModuleHandleA(None)?;
-        let class_name = CString::new("DiscordQuestWindow")?;
-        let window_title = CString::new(format!("DiscordQuest - {}", title))?;
+        let instance = GetModuleHandleW(None)?;
+        let class_name = to_wide_nul("DiscordQuestWindow");
+        let window_title = to_wide_nul(&format!("DiscordQuest - {}", title));
 
         // Dark background brush
         let brush = CreateSolidBrush(COLORREF(0x00161216));
 
-        let wc = WNDCLASSA {
+        let wc = WNDCLASSW {
             lpfnWndProc: Some(window_proc),
             hInstance: HINSTANCE(instance.0),
-            lpszClassName: PCSTR(class_name.as_ptr() as *const u8),
+            lpszClassName: PCWSTR(class_name.as_ptr()),
             hbrBackground: brush,
             ..Default::default()
         };
 
-        RegisterClassA(&wc);
+        RegisterClassW(&wc);
 
         // Fixed window (not resizable) with caption and minimize
         let style = WS_CAPTION | WS_SYSMENU | WS_MINIMIZEBOX;
 
-        let hwnd = CreateWindowExA(
+        let hwnd = CreateWindowExW(
             WINDOW_EX_STYLE(0),
-            PCSTR(class_name.as_ptr() as *const u8),
-            PCSTR(window_title.as_ptr() as *const u8),
+            PCWSTR(class_name.as_ptr()),
+            PCWSTR(window_title.as_ptr()),
             style,
             CW_USEDEFAULT,
             CW_USEDEFAULT,
@@ -305,13 +289,15 @@ fn create_native_window(title: &str) -> Result<(HWND, HINSTANCE), Box<dyn std::e
         ); 
         match hwnd {
             Ok(hwnd) if !hwnd.0.is_null() => Ok((hwnd, HINSTANCE(instance.0))),
-            _ => Err("Error al crear ventana".into()),
+            _ => Err("Could not create the window".into()),
         }
     }
 }
 
 fn main() {
-    let config = parse_args();
+    // args_os: a title that is not valid Unicode must not panic the stub
+    let mut config = StubArgs::parse(std::env::args_os().skip(1).map(|arg| arg.to_string_lossy().into_owned()));
+    config.title = clean_title(&config.title);
 
     // Store start time
     let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs();
@@ -319,10 +305,14 @@ fn main() {
 
     // Store title globally for paint
     GAME_TITLE.set(config.title.clone()).ok();
+    UI_LANG.set(config.lang).ok();
+    if let Some(max_runtime) = config.max_runtime {
+        MAX_RUNTIME_SECS.store(max_runtime.as_secs(), Ordering::Relaxed);
+    }
     
     let tray_menu = tray_icon::menu::Menu::new();
-    let quit_i = tray_icon::menu::MenuItem::new("Cerrar", true, None);
-    let show_i = tray_icon::menu::MenuItem::new("Mostrar", true, None);
+    let quit_i = tray_icon::menu::MenuItem::new(i18n::text(config.lang, "stub.tray_close"), true, None);
+    let show_i = tray_icon::menu::MenuItem::new(i18n::text(config.lang, "stub.tray_show"), true, None);
 
     let _tray_menu = tray_menu.append_items(&[
         &show_i,
@@ -330,40 +320,60 @@ fn main() {
         &quit_i
     ]);
 
-    let _tray = create_tray_icon(tray_menu, &format!("DiscordQuest - {}", &config.title));
+    let _tray = create_tray_icon(tray_menu, &tooltip(&config.title));
 
     let (hwnd, _instance) = match create_native_window(&config.title) {
         Ok(result) => result,
         Err(e) => {
-            eprintln!("Error al crear ventana: {}", e);
+            eprintln!("Could not create the window: {}", e);
             return;
         }
     };
+    MAIN_WINDOW.store(hwnd.0 as isize, Ordering::Relaxed);
+
+    if let Some(parent_pid) = config.parent_pid {
+        watch_parent(parent_pid);
+    }
+
+    // Without the channel the app falls back to taskkill, so keep running either way
+    let ipc = match Server::start(std::process::id(), StubControl) {
+        Ok(server) => Some(server),
+        Err(e) => {
+            eprintln!("Could not open the control channel: {}", e);
+            None
+        }
+    };
     
     unsafe { 
-        let _ = ShowWindow(hwnd, SW_SHOWNORMAL);
+        // With --tray the window stays hidden until "Mostrar" or the app asks for it
+        if !config.start_minimized {
+            let _ = ShowWindow(hwnd, SW_SHOWNORMAL);
+        }
         
         let mut msg = MSG::default();
         loop {
             // Handle tray events
             if let Ok(event) = tray_icon::menu::MenuEvent::receiver().try_recv() {
                 if event.id == quit_i.id() {
+                    if let Some(ipc) = &ipc {
+                        ipc.broadcast(Event::TrayClosed);
+                    }
                     PostQuitMessage(0);
                 }
 
                 if event.id == show_i.id() {
                     let _ = ShowWindow(hwnd, SW_SHOWNORMAL);
-                    let _ = windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow(hwnd);
+                    let _ = SetForegroundWindow(hwnd);
                 }
             }
 
-            let ret = GetMessageA(&mut msg, None, 0, 0);
+            let ret = GetMessageW(&mut msg, None, 0, 0);
             if ret.0 == 0 || ret.0 == -1 {
                 break;
             }
             
             let _ = TranslateMessage(&msg);
-            DispatchMessageA(&msg);
+            DispatchMessageW(&msg);
         }
     }
 }
\ No newline at end of file
@@ -20,9 +20,247 @@ export interface Game {
     is_installed?: boolean;
 }
 
+export type CommandErrorCode =
+  | 'InvalidPath'
+  | 'DirCreateFailed'
+  | 'ResourceMissing'
+  | 'CopyFailed'
+  | 'SpawnFailed'
+  | 'LimitReached'
+  | 'AlreadyRunning'
+  | 'NotRunning'
+  | 'NotOwned'
+  | 'InvalidSettings'
+  | 'SettingsSaveFailed'
+  | 'InventoryFailed'
+  | 'StubUnreachable'
+  | 'HistoryFailed'
+  | 'InvalidCatalog'
+  | 'CatalogUnavailable'
+  | 'CatalogNotLoaded';
+
+export interface CommandError {
+  code: CommandErrorCode;
+  message: string;
+}
+
+export interface CreatedGame {
+  executable_path: string;
+  deployment: {
+    outcome: 'unchanged' | 'created' | 'updated';
+    linked: boolean;
+  };
+}
+
+export interface StubStatus {
+  pid: number;
+  title: string;
+  elapsed_secs: number;
+}
+
+export interface GameEntry {
+  app_id: string;
+  path: string;
+  executable_name: string;
+  size_bytes: number;
+  linked: boolean;
+  last_used: number;
+  ever_launched: boolean;
+}
+
+export interface GamesInventory {
+  entries: GameEntry[];
+  total_bytes: number;
+}
+
+export interface CleanupReport {
+  removed: GameEntry[];
+  skipped_running: GameEntry[];
+  failed: { entry: GameEntry; error: string }[];
+  freed_bytes: number;
+}
+
+export type Language = 'es' | 'en';
+
+export type ExitBehavior = 'stop_stubs' | 'keep_stubs';
+
+export interface Settings {
+  version: number;
+  /** Absolute path; null keeps the games folder next to the app. */
+  games_dir: string | null;
+  language: Language;
+  /** Between 100 and 60000. */
+  stop_grace_ms: number;
+  start_minimized: boolean;
+  /** 0 means no limit. */
+  max_concurrent_stubs: number;
+  /** Minutes before a stub closes on its own; 0 means never. */
+  max_runtime_mins: number;
+  exit_behavior: ExitBehavior;
+}
+
 export interface GameActionsProvider {
   canPlayGame: (game: Game | null) => boolean;
   isGameInstalled: (game: Game | null) => boolean;
   isExecutableRunning: (executable: GameExecutable) => boolean;
   isGameExecutableInstalled: (executable: GameExecutable) => boolean;
-}
\ No newline at end of file
+}
+
+export type ExitReason = 'exited' | 'tray_closed' | 'stopped' | 'lost';
+
+export interface Session {
+  app_id: string;
+  executable_name: string;
+  game_name: string;
+  pid: number;
+  started_at: number;
+  ended_at: number;
+  duration_secs: number;
+  exit_reason: ExitReason;
+  exit_code: number | null;
+}
+
+export interface HistoryQuery {
+  app_id?: string;
+  from?: number;
+  to?: number;
+}
+
+export type ExportFormat = 'json' | 'csv';
+
+interface ProcessInfo {
+  app_id: string;
+  executable_name: string;
+  game_name: string;
+  pid: number;
+  /** Unix seconds when the event happened. */
+  timestamp: number;
+  /** Seconds since the process started. */
+  duration_secs: number;
+}
+
+/** Result of `stop_process`. */
+export type StopOutcome = 'graceful' | 'forced' | 'already_exited' | 'failed';
+
+/** Payload of the `process_lifecycle` event. */
+export type LifecycleEvent =
+  | (ProcessInfo & { kind: 'started' })
+  | (ProcessInfo & { kind: 'stop_requested' })
+  | (ProcessInfo & { kind: 'exited'; exit_code: number | null })
+  | (ProcessInfo & { kind: 'force_killed' })
+  | (ProcessInfo & { kind: 'stop_finished'; outcome: StopOutcome })
+  | (ProcessInfo & { kind: 'monitor_error'; error: string });
+
+/** Result of `run_background_process`. */
+export type LaunchOutcome =
+  | { status: 'started'; pid: number; executable_path: string }
+  | { status: 'queued'; position: number; executable_path: string };
+
+/** Entry of `get_launch_queue` and of the `launch_queue` event payload. */
+export interface QueuedLaunch {
+  app_id: string;
+  executable_name: string;
+  game_name: string;
+  /** Unix seconds when the launch was queued. */
+  queued_at: number;
+}
+
+/** Payload of the `queued_launch_failed` event. */
+export type QueuedLaunchFailed = QueuedLaunch & { error: string };
+
+/** Entry of `get_active_processes`. */
+export interface ActiveProcess {
+  /** `app_id:executable_name`. */
+  key: string;
+  app_id: string;
+  executable_name: string;
+  game_name: string;
+  pid: number;
+  /** Unix seconds. */
+  started_at: number;
+  uptime_secs: number;
+  /** `stopping` between a stop request and the exit. */
+  state: 'running' | 'stopping';
+  executable_path: string;
+}
+
+/** Entry of `get_stub_resources` and of the `stub_resources` event payload. */
+export interface StubResources {
+  app_id: string;
+  executable_name: string;
+  pid: number;
+  memory_bytes: number;
+  virtual_memory_bytes: number;
+  /** 100 is one core fully busy. */
+  cpu_percent: number;
+  /** Handles on Windows, file descriptors elsewhere. */
+  handles: number | null;
+  /** Windows and Linux only. */
+  threads: number | null;
+  /** Unix seconds. */
+  timestamp: number;
+}
+
+/** What `parse_catalog` reports about an entry it had to skip or trim. */
+export type CatalogIssueKind =
+  | { kind: 'not_an_object' }
+  | { kind: 'missing_field'; field: string }
+  | { kind: 'invalid_field'; field: string }
+  | { kind: 'invalid_executable'; executable: number }
+  | { kind: 'duplicate_id' };
+
+export type CatalogIssue = CatalogIssueKind & {
+  /** Position in the catalog array. */
+  index: number;
+  id: string | null;
+};
+
+/** Result of `parse_catalog`. */
+export interface Catalog {
+  games: Game[];
+  issues: CatalogIssue[];
+}
+
+export interface MirrorMeta {
+  last_updated: string;
+  etag: string | null;
+  source_url: string;
+  sha256: string;
+  status: string;
+  items_count: number | null;
+}
+
+/** Result of `load_catalog`. The games themselves are reached through `search_catalog`. */
+export interface CatalogSummary {
+  game_count: number;
+  /** Entries that had to be skipped or trimmed. */
+  issues: CatalogIssue[];
+  /** `stale_cache`: the mirror could not be reached or verified. */
+  origin: 'downloaded' | 'cache' | 'stale_cache';
+  sha256: string;
+  /** Matches the sha256 published in `meta.json`. */
+  verified: boolean;
+  /** Unix seconds when this content was downloaded. */
+  fetched_at: number;
+  meta: MirrorMeta | null;
+  problems: { kind: 'unreachable' | 'http_status' | 'hash_mismatch' | 'invalid' | 'meta_unavailable'; url?: string }[];
+}
+
+/** One result of `search_catalog`. */
+export interface SearchHit {
+  game: Game;
+  score: number;
+  field: 'name' | 'alias';
+  /** The name or alias that matched. */
+  matched_text: string;
+  /** `[start, end)` offsets into `matched_text`, usable with `String.slice`. */
+  ranges: [number, number][];
+}
+
+/** Result of `search_catalog`. */
+export interface SearchPage {
+  /** Hits across all pages. */
+  total: number;
+  offset: number;
+  hits: SearchHit[];
+}